@@ -0,0 +1,69 @@
+//! Compares looking words up through the dictionary's index with scanning its entries, as lookups used to.
+//! Run with `cargo bench`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use valkyrie::{context::Context, dictionary::Dictionary};
+
+const WORDS: usize = 1000;
+const LOOKUPS: usize = 200_000;
+
+fn time(f: impl FnOnce()) -> Duration {
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+fn main() {
+    let mut d = Dictionary::new(WORDS);
+    for i in 0..WORDS {
+        d.insert(Some(i), i).unwrap();
+    }
+
+    let indexed = time(|| {
+        for i in 0..LOOKUPS {
+            black_box(d.get(black_box(i % WORDS)));
+        }
+    });
+
+    let scanned = time(|| {
+        for i in 0..LOOKUPS {
+            let key = Some(black_box(i % WORDS));
+            black_box(d.dictionary().iter().find(|(k, _)| *k == key));
+        }
+    });
+
+    println!(
+        "{} lookups in {} entries: indexed {:?}, scanned {:?}",
+        LOOKUPS, WORDS, indexed, scanned
+    );
+
+    // Interpreting a line looks up every token, so a large dictionary shouldn't slow it down.
+    let mut small = Context::new(1024, WORDS * 2);
+    let mut large = Context::new(1024, WORDS * 2);
+    for i in 0..WORDS {
+        large.eval(format!(": word-{} {} ;", i, i)).unwrap();
+    }
+
+    let line = "1 2 + drop ".repeat(100);
+    let runs = 200;
+    let small_time = time(|| {
+        for _ in 0..runs {
+            small.eval(line.clone()).unwrap();
+        }
+    });
+    let large_time = time(|| {
+        for _ in 0..runs {
+            large.eval(line.clone()).unwrap();
+        }
+    });
+
+    println!(
+        "{} evaluations: {} definitions {:?}, {} definitions {:?}",
+        runs,
+        small.dictionary().len(),
+        small_time,
+        large.dictionary().len(),
+        large_time
+    );
+}
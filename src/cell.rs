@@ -0,0 +1,395 @@
+use std::convert::TryFrom;
+use std::fmt::{Debug, Display};
+use std::num::ParseIntError;
+use std::ops::{Add, BitAnd, BitOr, BitXor, Mul, Neg, Not, Sub};
+
+/// A value that fits in one cell of a context's stacks and data space.
+pub trait Cell:
+    Copy
+    + Debug
+    + Display
+    + Default
+    + Ord
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+    + 'static
+{
+    /// A value twice as wide, for double-cell numbers and intermediate results that must not overflow.
+    type Double: DoubleCell;
+
+    /// The number of bytes a cell takes up in the data space.
+    const SIZE: usize;
+    const ZERO: Self;
+
+    /// Widens the cell, keeping its sign.
+    fn to_double(self) -> Self::Double;
+
+    /// Narrows a double, keeping its low bits.
+    fn wrapping_narrow(d: Self::Double) -> Self;
+
+    /// Narrows a double, or returns `None` if it does not fit.
+    fn checked_narrow(d: Self::Double) -> Option<Self>;
+
+    /// Narrows a double, clamping it to the smallest or largest cell.
+    fn saturating_narrow(d: Self::Double) -> Self;
+
+    /// Splits a double into its low and high cells.
+    fn split(d: Self::Double) -> (Self, Self);
+
+    /// Joins a low and a high cell into a double.
+    fn join(low: Self, high: Self) -> Self::Double;
+
+    /// Compares the cells as if they were unsigned.
+    fn unsigned_lt(self, other: Self) -> bool;
+
+    /// Multiplies the cells as if they were unsigned, giving an unsigned double.
+    fn unsigned_mul(self, other: Self) -> Self::Double;
+
+    /// Divides an unsigned double by a non-zero unsigned cell. Returns the remainder, the quotient with only its
+    /// low bits kept, and whether the quotient did not fit in a cell.
+    fn unsigned_div_mod(ud: Self::Double, u: Self) -> (Self, Self, bool);
+
+    /// Shifts the bits left, giving zero if every bit would be shifted out.
+    fn logical_shl(self, places: Self) -> Self;
+
+    /// Shifts the bits right without extending the sign, giving zero if every bit would be shifted out.
+    fn logical_shr(self, places: Self) -> Self;
+
+    fn wrapping_add(self, other: Self) -> Self;
+    fn wrapping_sub(self, other: Self) -> Self;
+    fn wrapping_mul(self, other: Self) -> Self;
+
+    /// Converts an address or a count to a cell, keeping its low bits.
+    fn from_usize(n: usize) -> Self;
+
+    /// Converts the cell to an address, reading it as unsigned so that every address the cell can hold is reachable.
+    fn to_usize(self) -> usize;
+
+    fn to_isize(self) -> isize;
+
+    fn to_f64(self) -> f64;
+
+    /// Converts a float to a cell, truncating towards zero and clamping it to the smallest or largest cell.
+    fn from_f64(r: f64) -> Self;
+
+    /// Returns the little endian bytes the cell is stored as in the data space.
+    fn to_bytes(self) -> Vec<u8>;
+
+    /// Reads a cell from exactly `SIZE` little endian bytes.
+    fn from_bytes(bytes: &[u8]) -> Self;
+
+    /// Parses a cell written in the given radix, which must be from 2 to 36.
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseIntError>;
+}
+
+/// The operations needed on `Cell::Double`.
+pub trait DoubleCell:
+    Copy
+    + Debug
+    + Display
+    + Ord
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn abs(self) -> Self;
+    fn wrapping_div(self, other: Self) -> Self;
+    fn wrapping_rem(self, other: Self) -> Self;
+    fn wrapping_add(self, other: Self) -> Self;
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn saturating_add(self, other: Self) -> Self;
+    fn wrapping_sub(self, other: Self) -> Self;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn saturating_sub(self, other: Self) -> Self;
+    fn to_f64(self) -> f64;
+
+    /// Converts a float to a double, truncating towards zero and clamping it to the smallest or largest double.
+    fn from_f64(r: f64) -> Self;
+
+    /// Parses a double written in the given radix, which must be from 2 to 36.
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseIntError>;
+
+    /// Writes the double in the given radix, which must be from 2 to 36, with upper case digits.
+    fn to_str_radix(self, radix: u32) -> String;
+
+    /// Divides the double by a non-zero radix as if it were unsigned, returning the quotient and the remainder.
+    fn unsigned_div_rem(self, radix: u32) -> (Self, u32);
+}
+
+macro_rules! impl_cell {
+    ($cell:ty, $unsigned:ty, $double:ty, $unsigned_double:ty) => {
+        impl Cell for $cell {
+            type Double = $double;
+
+            const SIZE: usize = std::mem::size_of::<$cell>();
+            const ZERO: Self = 0;
+
+            fn to_double(self) -> $double {
+                self as $double
+            }
+
+            fn wrapping_narrow(d: $double) -> Self {
+                d as $cell
+            }
+
+            fn checked_narrow(d: $double) -> Option<Self> {
+                <$cell>::try_from(d).ok()
+            }
+
+            fn saturating_narrow(d: $double) -> Self {
+                d.clamp(<$cell>::MIN as $double, <$cell>::MAX as $double) as $cell
+            }
+
+            fn split(d: $double) -> (Self, Self) {
+                (d as $cell, (d >> <$cell>::BITS) as $cell)
+            }
+
+            fn join(low: Self, high: Self) -> $double {
+                ((high as $double) << <$cell>::BITS) | (low as $unsigned as $double)
+            }
+
+            fn unsigned_lt(self, other: Self) -> bool {
+                (self as $unsigned) < (other as $unsigned)
+            }
+
+            fn unsigned_mul(self, other: Self) -> $double {
+                ((self as $unsigned as $unsigned_double) * (other as $unsigned as $unsigned_double))
+                    as $double
+            }
+
+            fn unsigned_div_mod(ud: $double, u: Self) -> (Self, Self, bool) {
+                let ud = ud as $unsigned_double;
+                let u = u as $unsigned as $unsigned_double;
+                let quotient = ud / u;
+
+                (
+                    (ud % u) as $cell,
+                    quotient as $cell,
+                    quotient > <$unsigned>::MAX as $unsigned_double,
+                )
+            }
+
+            fn logical_shl(self, places: Self) -> Self {
+                u32::try_from(places)
+                    .ok()
+                    .and_then(|places| (self as $unsigned).checked_shl(places))
+                    .unwrap_or(0) as $cell
+            }
+
+            fn logical_shr(self, places: Self) -> Self {
+                u32::try_from(places)
+                    .ok()
+                    .and_then(|places| (self as $unsigned).checked_shr(places))
+                    .unwrap_or(0) as $cell
+            }
+
+            fn wrapping_add(self, other: Self) -> Self {
+                <$cell>::wrapping_add(self, other)
+            }
+
+            fn wrapping_sub(self, other: Self) -> Self {
+                <$cell>::wrapping_sub(self, other)
+            }
+
+            fn wrapping_mul(self, other: Self) -> Self {
+                <$cell>::wrapping_mul(self, other)
+            }
+
+            fn from_usize(n: usize) -> Self {
+                n as $cell
+            }
+
+            fn to_usize(self) -> usize {
+                self as $unsigned as usize
+            }
+
+            fn to_isize(self) -> isize {
+                self as isize
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn from_f64(r: f64) -> Self {
+                r as $cell
+            }
+
+            fn to_bytes(self) -> Vec<u8> {
+                self.to_le_bytes().to_vec()
+            }
+
+            fn from_bytes(bytes: &[u8]) -> Self {
+                let mut cell = [0; std::mem::size_of::<$cell>()];
+                cell.copy_from_slice(bytes);
+
+                <$cell>::from_le_bytes(cell)
+            }
+
+            fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseIntError> {
+                <$cell>::from_str_radix(s, radix)
+            }
+        }
+
+        impl DoubleCell for $double {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn abs(self) -> Self {
+                <$double>::abs(self)
+            }
+
+            fn wrapping_div(self, other: Self) -> Self {
+                <$double>::wrapping_div(self, other)
+            }
+
+            fn wrapping_rem(self, other: Self) -> Self {
+                <$double>::wrapping_rem(self, other)
+            }
+
+            fn wrapping_add(self, other: Self) -> Self {
+                <$double>::wrapping_add(self, other)
+            }
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$double>::checked_add(self, other)
+            }
+
+            fn saturating_add(self, other: Self) -> Self {
+                <$double>::saturating_add(self, other)
+            }
+
+            fn wrapping_sub(self, other: Self) -> Self {
+                <$double>::wrapping_sub(self, other)
+            }
+
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                <$double>::checked_sub(self, other)
+            }
+
+            fn saturating_sub(self, other: Self) -> Self {
+                <$double>::saturating_sub(self, other)
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn from_f64(r: f64) -> Self {
+                r as $double
+            }
+
+            fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseIntError> {
+                <$double>::from_str_radix(s, radix)
+            }
+
+            fn to_str_radix(self, radix: u32) -> String {
+                let mut magnitude = self.unsigned_abs();
+                let mut digits = vec![];
+
+                loop {
+                    let digit = (magnitude % radix as $unsigned_double) as u32;
+                    digits.push(std::char::from_digit(digit, radix).unwrap_or('?'));
+                    magnitude /= radix as $unsigned_double;
+
+                    if magnitude == 0 {
+                        break;
+                    }
+                }
+
+                if self < 0 {
+                    digits.push('-');
+                }
+
+                digits.iter().rev().collect::<String>().to_ascii_uppercase()
+            }
+
+            fn unsigned_div_rem(self, radix: u32) -> (Self, u32) {
+                let ud = self as $unsigned_double;
+                let radix = radix as $unsigned_double;
+
+                ((ud / radix) as $double, (ud % radix) as u32)
+            }
+        }
+    };
+}
+
+impl_cell!(i16, u16, i32, u32);
+impl_cell!(i32, u32, i64, u64);
+impl_cell!(i64, u64, i128, u128);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_and_join_round_trip() {
+        assert_eq!((-1, -1), i16::split(-1));
+        assert_eq!((0, 1), i32::split(1 << 32));
+        assert_eq!(-5, i64::join(-5, -1));
+        assert_eq!(1 << 64, i64::join(0, 1));
+    }
+
+    #[test]
+    fn narrow_follows_width() {
+        assert_eq!(i16::MIN, i16::wrapping_narrow(32768));
+        assert_eq!(None, i16::checked_narrow(32768));
+        assert_eq!(Some(-32768), i16::checked_narrow(-32768));
+        assert_eq!(i16::MAX, i16::saturating_narrow(100000));
+        assert_eq!(i64::MIN, i64::saturating_narrow(i128::MIN));
+    }
+
+    #[test]
+    fn unsigned_operations_reinterpret_sign_bit() {
+        assert!(1i16.unsigned_lt(-1));
+        assert_eq!(i32::join(1, -2), (-1i32).unsigned_mul(-1));
+        assert_eq!((0, i16::MIN, false), i16::unsigned_div_mod(65536, 2));
+        assert!(i16::unsigned_div_mod(131072, 2).2);
+    }
+
+    #[test]
+    fn logical_shifts_clear_out_of_range() {
+        assert_eq!(16, 1i64.logical_shl(4));
+        assert_eq!(0, 1i16.logical_shl(16));
+        assert_eq!(0, 1i32.logical_shl(-1));
+        assert_eq!(0x7fff, (-1i16).logical_shr(1));
+        assert_eq!(0, (-1i64).logical_shr(64));
+    }
+
+    #[test]
+    fn from_f64_truncates_and_clamps() {
+        assert_eq!(-2, <i32 as Cell>::from_f64(-2.9));
+        assert_eq!(i16::MAX, i16::from_f64(1e9));
+        assert_eq!(0, <i64 as Cell>::from_f64(f64::NAN));
+        assert_eq!(1 << 40, <i64 as Cell>::from_f64(1099511627776.5));
+    }
+
+    #[test]
+    fn to_str_radix_writes_upper_case_digits() {
+        assert_eq!("FF", 255i32.to_str_radix(16));
+        assert_eq!("-1010", (-10i64).to_str_radix(2));
+        assert_eq!("0", 0i128.to_str_radix(36));
+        assert_eq!(
+            "-170141183460469231731687303715884105728",
+            i128::MIN.to_str_radix(10)
+        );
+    }
+
+    #[test]
+    fn unsigned_div_rem_ignores_sign() {
+        assert_eq!((12, 3), 123i64.unsigned_div_rem(10));
+        assert_eq!((0x7fff_ffff, 1), (-1i32).unsigned_div_rem(2));
+    }
+
+    #[test]
+    fn bytes_are_little_endian() {
+        assert_eq!(vec![2, 1], 258i16.to_bytes());
+        assert_eq!(258, i64::from_bytes(&[2, 1, 0, 0, 0, 0, 0, 0]));
+    }
+}
@@ -1,4 +1,10 @@
-use crate::{dictionary, id::Id, stack};
+use crate::{
+    cell::{Cell, DoubleCell},
+    data_space, dictionary,
+    id::{Id, Interner},
+    stack,
+};
+use std::convert::TryFrom;
 use std::rc::Rc;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -7,6 +13,65 @@ pub enum Mode {
     Compiling,
 }
 
+/// How integer division rounds a quotient that is not exact.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Division {
+    /// Rounds towards zero, so the remainder has the sign of the dividend. `-7 2 /mod` gives `-1 -3`.
+    Symmetric,
+    /// Rounds towards negative infinity, so the remainder has the sign of the divisor. `-7 2 /mod` gives `1 -4`.
+    Floored,
+}
+
+/// The order `-` and `/` take their operands in.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum OperandOrder {
+    /// The second item is the left operand, so `1 2 -` gives -1 and `8 2 /` gives 4.
+    Standard,
+    /// The top item is the left operand, so `1 2 -` gives 1 and `2 8 /` gives 4.
+    /// This was the only order before the standard one was adopted, and is kept so older scripts can migrate.
+    Reversed,
+}
+
+/// What arithmetic does when a result does not fit in a cell.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Overflow {
+    /// Keeps the low bits of the result, like a classic Forth. With 32-bit cells, `2147483647 1 +` gives -2147483648.
+    Wrapping,
+    /// Returns `ContextErr::ArithmeticOverflow`, leaving the operands off the stack.
+    Checked,
+    /// Clamps the result to the largest or smallest cell. With 32-bit cells, `2147483647 1 +` gives 2147483647.
+    Saturating,
+}
+
+/// Whether the case of a word's name matters when it is defined or looked up.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Case {
+    /// Names are folded to lower case, so `DUP`, `Dup` and `dup` are the same word, as in standard Forth.
+    Insensitive,
+    /// Names must match exactly, so `DUP` and `dup` may be different words.
+    Sensitive,
+}
+
+/// Options that change how a context evaluates code. They are kept when the context is reset.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Settings {
+    pub division: Division,
+    pub operand_order: OperandOrder,
+    pub overflow: Overflow,
+    pub case: Case,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            division: Division::Symmetric,
+            operand_order: OperandOrder::Standard,
+            overflow: Overflow::Wrapping,
+            case: Case::Insensitive,
+        }
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum Return {
     Ok,
@@ -20,7 +85,39 @@ pub enum ContextErr {
     DivideByZero,
     Parse(std::num::ParseIntError),
     DictionaryErr(dictionary::DictionaryErr),
+    DataSpaceErr(data_space::DataSpaceErr),
     AccessedUndefinedAtAddr(usize),
+    /// A word that only has meaning inside a definition was used while interpreting.
+    CompileOnly(String),
+    /// A `:` was encountered while a definition was already being compiled.
+    NestedDefinition,
+    ReturnStackErr(stack::StackErr),
+    FloatStackErr(stack::StackErr),
+    /// The return stack did not hold the kind of entry an operation expected, such as `r>` finding a return address.
+    ReturnStackMismatch,
+    /// A control-flow word had nothing to resolve, or a definition ended with unresolved control flow.
+    UnbalancedControlFlow(String),
+    /// A word that parses a name found the end of the line instead.
+    MissingName,
+    /// `does>` was executed before any word was made with `create`.
+    DoesWithoutCreate,
+    /// `to` was given a name that was not made with `value`.
+    NotAValue(String),
+    /// The result of an arithmetic word did not fit in a cell, and the settings ask for overflow to be checked.
+    ArithmeticOverflow,
+    /// `base` held a radix outside 2 to 36 when a number was parsed or displayed.
+    InvalidBase,
+    /// Pictured numeric output held more characters than its buffer has room for.
+    PicturedOutputOverflow,
+    /// `c"` was given more text than a counted string's length byte can describe, or `s"` more than a transient
+    /// string buffer holds.
+    StringTooLong,
+    /// A word that looks up a definition by name, such as `forget`, was given a name that isn't defined.
+    UndefinedWord(String),
+    /// `forget` was given a word defined by `reset`, such as a builtin.
+    Protected(String),
+    /// `yield` was called, but resuming the rest of the line afterwards isn't supported yet.
+    YieldUnsupported,
 }
 
 impl From<stack::StackErr> for ContextErr {
@@ -41,60 +138,251 @@ impl From<dictionary::DictionaryErr> for ContextErr {
     }
 }
 
-pub type Procedure = Box<dyn Fn(&mut Context) -> Result<(), ContextErr>>;
+impl From<data_space::DataSpaceErr> for ContextErr {
+    fn from(de: data_space::DataSpaceErr) -> Self {
+        match de {
+            data_space::DataSpaceErr::UndefinedAccess(addr) => Self::AccessedUndefinedAtAddr(addr),
+            de => Self::DataSpaceErr(de),
+        }
+    }
+}
+
+pub type Procedure<C> = Box<dyn Fn(&mut Context<C>) -> Result<(), ContextErr>>;
 
 macro_rules! builtin_word {
     ($context:ident : $word:expr => $execution:expr) => {
-        let action: Procedure = { Box::new($execution) };
+        let action: Procedure<C> = { Box::new($execution) };
 
-        $context
-            .dictionary
-            .insert(Some($word.into()), Rc::new(Word::Builtin(action)))?;
+        $context.define($word, Word::Builtin(action))?;
+    };
+    ($context:ident : $word:expr, immediate => $execution:expr) => {
+        let action: Procedure<C> = { Box::new($execution) };
+
+        $context.define($word, Word::Immediate(action))?;
     };
 }
 
-pub enum Word {
-    Builtin(Procedure),
-    /// A custom, user defined word. If multiple words are chained together to make up this word, they are stored in the body and pushed to the call stack. The size of 13 is arbitrary, and open to change.
+pub enum Word<C: Cell> {
+    Builtin(Procedure<C>),
+    /// A builtin that is executed even while compiling, such as `;`.
+    Immediate(Procedure<C>),
+    /// A custom, user defined word. The body is threaded code that is executed from the first instruction until an `Exit` is reached.
     Custom {
-        body: [Rc<Word>; 13],
+        body: Vec<Instruction<C>>,
     },
-    Data(Datum),
+    /// A word made by `value`. Pushes the cell stored at its address, which `to` may change.
+    Value(data_space::Addr),
+    /// A word made by `create`. Pushes the address of its data field, then runs the code attached by `does>`, if any.
+    Created {
+        addr: data_space::Addr,
+        does: Option<Resume<C>>,
+    },
+    Data(C),
+    /// A word made by `fconstant`. Pushes the value onto the float stack.
+    Float(f64),
 }
 
-impl std::fmt::Debug for Word {
+impl<C: Cell> std::fmt::Debug for Word<C> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Word::Builtin(_) => f.write_str(&format!("Builtin, can't deal")),
+            Word::Builtin(_) => f.write_str("Builtin, can't deal"),
+            Word::Immediate(_) => f.write_str("Immediate, can't deal"),
             Word::Custom { body } => f.write_str(&format!("Custom {:?}", body)),
+            Word::Value(addr) => f.write_str(&format!("Value at {:?}", addr)),
+            Word::Created { addr, does } => f.write_str(&format!(
+                "Created at {:?}, does> {:?}",
+                addr,
+                does.is_some()
+            )),
             Word::Data(d) => f.write_str(&format!("Data: {:?}", d)),
+            Word::Float(r) => f.write_str(&format!("Float: {:?}", r)),
         }
     }
 }
 
-/// The basic types that may be put on the stack
+/// A word and the index of the instruction to continue executing it from.
+type Resume<C> = (Rc<Word<C>>, usize);
+
+/// A single step of compiled code in the body of a custom word.
+#[derive(Debug, Clone)]
+pub enum Instruction<C: Cell> {
+    /// Executes the given word.
+    Call(Rc<Word<C>>),
+    /// Pushes the value onto the stack.
+    Literal(C),
+    /// Pushes the value onto the float stack.
+    FloatLiteral(f64),
+    /// Continues execution at the given index of the body.
+    Branch(usize),
+    /// Pops a flag off the stack, continuing execution at the given index of the body if it is zero (false).
+    /// Any other value, canonically -1, is true.
+    BranchIfZero(usize),
+    /// Calls the word currently being executed.
+    Recurse,
+    /// Pops an index and a limit off the stack and starts a counted loop.
+    Do,
+    /// Like `Do`, but continues execution at the given index instead if the index and limit are equal.
+    QuestionDo(usize),
+    /// Adds one to the loop index, continuing at the given index of the body unless the loop is finished.
+    Loop(usize),
+    /// Pops a step off the stack and adds it to the loop index, continuing at the given index of the body unless
+    /// the index crossed the boundary between the limit minus one and the limit.
+    PlusLoop(usize),
+    /// Discards the current loop and continues execution at the given index of the body.
+    Leave(usize),
+    /// Attaches the rest of the body to the most recently created word, then returns from the current word.
+    Does,
+    /// Returns from the current word.
+    Exit,
+}
+
+/// An entry on the return stack.
+#[derive(Debug, Clone)]
+pub enum Frame<C: Cell> {
+    /// Where to resume execution once the called word exits.
+    Return { word: Rc<Word<C>>, ip: usize },
+    /// A value placed on the return stack by `>r`.
+    Data(C),
+    /// The parameters of a counted loop.
+    Loop { index: C, limit: C },
+}
+
+/// An unresolved piece of control flow, kept on the control-flow stack while compiling.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Control {
+    /// The index of a forward branch that still needs a target.
+    Orig(usize),
+    /// The index of the first instruction in the body of a counted loop.
+    Do(usize),
+    /// The index a backward branch should jump to.
+    Dest(usize),
+}
+
+/// A number parsed from the input.
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Number<C: Cell> {
+    Single(C),
+    /// Written with a trailing `.`, and put on the stack as two cells.
+    Double(C::Double),
+    /// Written with an exponent, such as `1.5e0`, and put on the float stack.
+    Float(f64),
+}
+
+/// The number of bytes a float takes up in the data space.
+const FLOAT_SIZE: usize = std::mem::size_of::<f64>();
+
+/// The number of bytes in each buffer `s"` copies interpreted strings to, the same as the longest counted string.
+const STRING_BUFFER_SIZE: usize = u8::MAX as usize;
+
+/// The number of buffers interpreted `s"` strings are copied to in turn.
+const STRING_BUFFERS: usize = 2;
+
+/// The most spaces written at once to pad output, so a huge count from the stack can't exhaust memory.
+const MAX_SPACES: usize = u16::MAX as usize;
+
+/// The basic type that may be put on the stack, unless a context is made with another `Cell` type.
+/// Doubles take up two cells on the stack, with the high cell on top.
 pub type Datum = i32;
 
-pub struct Context {
-    stack: stack::Stack<Datum>,
+pub struct Context<C: Cell = Datum> {
+    settings: Settings,
+    stack: stack::Stack<C>,
+    return_stack: stack::Stack<Frame<C>>,
+    float_stack: stack::Stack<f64>,
     mode: Mode,
-    dictionary: dictionary::Dictionary<Id, Rc<Word>>,
-    fsm: Fsm,
+    dictionary: dictionary::Dictionary<Id, Rc<Word<C>>>,
+    /// The names of every word that has been defined, which the dictionary refers to by `Id`.
+    names: Interner,
+    /// The name of each dictionary entry as it was spelled when defined, which may differ from its key in case.
+    spellings: Vec<Id>,
+    /// Where `here` was before each dictionary entry was defined, for `forget` and markers to restore.
+    origins: Vec<data_space::Addr>,
+    /// The number of dictionary entries made by `reset`, which `forget` may not remove.
+    fence: dictionary::Addr,
+    data_space: data_space::DataSpace,
+    /// The line being evaluated, and how far into it has been parsed.
+    input: String,
+    input_pos: usize,
+    definition: Option<Definition<C>>,
+    /// The address of the cell holding the current base.
+    base: data_space::Addr,
+    /// The address of the buffer `#>` copies pictured numeric output to.
+    pictured: data_space::Addr,
+    /// The addresses of the buffers interpreted `s"` strings are copied to, used in turn so the most recent strings
+    /// are kept.
+    strings: [data_space::Addr; STRING_BUFFERS],
+    /// The index of the buffer in `strings` the next interpreted `s"` uses.
+    next_string: usize,
+    /// The characters of the number being converted by pictured numeric output, from `<#` to `#>`.
+    hold: Vec<u8>,
+    /// Text written by output words, waiting to be taken by the host.
+    output: String,
+    /// The name of the most recent word made by `create`, for `does>` to modify.
+    last_created: Option<Id>,
+    control_flow: stack::Stack<Control>,
+    /// The `leave` and `?do` branches of each counted loop being compiled, resolved by `loop` or `+loop`.
+    leaves: Vec<Vec<usize>>,
 }
 
-enum Fsm {
-    Execute,
-    GetVariable,
+/// A word that is currently being compiled.
+struct Definition<C: Cell> {
+    name: String,
+    body: Vec<Instruction<C>>,
+    /// Where `here` was when the definition started.
+    origin: data_space::Addr,
 }
 
 impl Context {
-    /// Creates a new context for interpreting.
+    /// Creates a new context for interpreting with `Datum` cells and the default settings. The data space has room
+    /// for one cell per dictionary entry.
     pub fn new(stack_capacity: usize, dictionary_capacity: usize) -> Self {
+        Self::with_settings(
+            stack_capacity,
+            dictionary_capacity,
+            dictionary_capacity * Datum::SIZE,
+            Settings::default(),
+        )
+    }
+}
+
+impl<C: Cell> Context<C> {
+    /// Creates a new context for interpreting. The return, control-flow and float stacks share the capacity of the data stack,
+    /// and the data space capacity is in bytes. The data space also gets room for `base` and the pictured numeric output
+    /// and string buffers on top of the capacity, but is made no larger than a cell can address.
+    pub fn with_settings(
+        stack_capacity: usize,
+        dictionary_capacity: usize,
+        data_space_capacity: usize,
+        settings: Settings,
+    ) -> Self {
         let mut forth = Self {
-            fsm: Fsm::Execute,
+            settings,
             stack: stack::Stack::new(stack_capacity),
+            return_stack: stack::Stack::new(stack_capacity),
+            float_stack: stack::Stack::new(stack_capacity),
             mode: Mode::Interpreting,
             dictionary: dictionary::Dictionary::new(dictionary_capacity),
+            names: Interner::new(),
+            spellings: vec![],
+            origins: vec![],
+            fence: 0,
+            data_space: data_space::DataSpace::new(
+                data_space_capacity
+                    .saturating_add(reserved_size::<C>())
+                    .min(max_address::<C>()),
+            ),
+            input: String::new(),
+            input_pos: 0,
+            base: 0,
+            pictured: 0,
+            strings: [0; STRING_BUFFERS],
+            next_string: 0,
+            hold: vec![],
+            output: String::new(),
+            definition: None,
+            last_created: None,
+            control_flow: stack::Stack::new(stack_capacity),
+            leaves: vec![],
         };
 
         forth.reset();
@@ -104,320 +392,4232 @@ impl Context {
 
     /// Resets the context to a pristine state.
     pub fn reset(&mut self) {
-        self.fsm = Fsm::Execute;
         self.dictionary.clear();
+        self.spellings.clear();
+        self.origins.clear();
+        self.data_space.clear();
         self.stack.clear();
-        self.mode = Mode::Interpreting;
+        self.return_stack.clear();
+        self.float_stack.clear();
+        self.hold.clear();
+        self.output.clear();
+        self.input.clear();
+        self.input_pos = 0;
+        self.next_string = 0;
+        self.end_definition();
+        self.last_created = None;
         self.set_primitives().unwrap();
+        self.fence = self.dictionary.dictionary().len();
+    }
+
+    /// Returns the settings the context was created with.
+    pub fn settings(&self) -> &Settings {
+        &self.settings
     }
 
     /// Pushes a new value onto the stack.
-    pub fn push(&mut self, data: Datum) -> Result<(), stack::StackErr> {
+    pub fn push(&mut self, data: C) -> Result<(), stack::StackErr> {
         self.stack.push(data)
     }
 
     /// Pops a value off the stack.
-    pub fn pop(&mut self) -> Result<Datum, stack::StackErr> {
+    pub fn pop(&mut self) -> Result<C, stack::StackErr> {
         self.stack.pop()
     }
 
     /// Returns a read-only handle to the stack.
-    pub fn stack(&self) -> &[Datum] {
-        &self.stack.data()
+    pub fn stack(&self) -> &[C] {
+        self.stack.data()
+    }
+
+    /// Returns a read-only handle to the float stack.
+    pub fn float_stack(&self) -> &[f64] {
+        self.float_stack.data()
+    }
+
+    /// Returns the text written by output words since it was last taken.
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    /// Takes the text written by output words, leaving the output empty.
+    pub fn take_output(&mut self) -> String {
+        std::mem::take(&mut self.output)
+    }
+
+    /// Returns the name of the word a dictionary key refers to, spelled as it was defined.
+    pub fn name(&self, id: Id) -> &str {
+        match self.dictionary.get_addr(id) {
+            Some(addr) => self.spelling(addr),
+            None => self.names.name(id),
+        }
     }
 
     /// Returns a read-only handle to the dictionary.
-    pub fn dictionary(&self) -> &[(Option<Id>, Rc<Word>)] {
+    pub fn dictionary(&self) -> &[(Option<Id>, Rc<Word<C>>)] {
         self.dictionary.dictionary()
     }
 
     /// Evaluates a line of code. By default, tokens are separated by whitespace.
+    /// If an error occurs, the return stack is cleared and any unfinished definition is discarded.
     pub fn eval(&mut self, line: String) -> Result<Return, ContextErr> {
+        let result = self.interpret(line);
+
+        if result.is_err() {
+            self.return_stack.clear();
+            self.end_definition();
+        }
+
+        result
+    }
+
+    fn interpret(&mut self, line: String) -> Result<Return, ContextErr> {
+        self.input = line;
+        self.input_pos = 0;
+
         // a) Skip leading spaces and parse a name (see 3.4.1);
-        for word_str in line.split_whitespace() {
-            match self.fsm {
-                Fsm::Execute => {
-                    match word_str {
-                        "bye" => {
-                            return Ok(Return::Shutdown);
-                        }
-                        "yield" => {
-                            todo!("There's a bug where yielding doesn't resume. It just chops off other stuff.");
-                            return Ok(Return::Yielding);
-                        }
-                        "var" => {
-                            // https://forth-standard.org/standard/core/VARIABLE
-                            // Idea: if you ever need to extend this, consider a FSM to wait for another input
-                            self.fsm = Fsm::GetVariable;
+        while let Some(word_str) = self.parse_name() {
+            let word_str = word_str.as_str();
+
+            match self.fold_case(word_str).as_str() {
+                "bye" => {
+                    return Ok(Return::Shutdown);
+                }
+                "yield" => {
+                    // There's a bug where yielding doesn't resume. It just chops off other stuff.
+                    return Err(ContextErr::YieldUnsupported);
+                }
+                _ => {
+                    // b) Search the dictionary name space (see 3.4.2).
+                    match (self.find_word(word_str), &self.mode) {
+                        (Some(word), Mode::Interpreting) => {
+                            self.run_word(word)?;
                         }
-                        _ => {
-                            // b) Search the dictionary name space (see 3.4.2).
-                            let word = match self.find_word(word_str) {
-                                Some(word) => word,
-                                None => {
-                                    let i = self.convert_to_number(word_str)?;
-
-                                    Rc::new(Word::Data(i))
-                                }
-                            };
-
-                            match self.mode {
-                                Mode::Interpreting => {
-                                    self.run_word(word)?;
-                                }
-                                Mode::Compiling => {
-                                    todo!("Compiling");
-                                }
+                        (Some(word), Mode::Compiling) => {
+                            if let Word::Immediate(_) = *word {
+                                self.run_word(word)?;
+                            } else {
+                                self.compile(Instruction::Call(word));
                             }
                         }
+                        (None, Mode::Interpreting) => match self.convert_to_number(word_str)? {
+                            Number::Single(n) => {
+                                self.stack.push(n)?;
+                            }
+                            Number::Double(d) => {
+                                self.push_double(d)?;
+                            }
+                            Number::Float(r) => {
+                                self.push_float(r)?;
+                            }
+                        },
+                        (None, Mode::Compiling) => match self.convert_to_number(word_str)? {
+                            Number::Single(n) => {
+                                self.compile(Instruction::Literal(n));
+                            }
+                            Number::Double(d) => {
+                                let (low, high) = C::split(d);
+                                self.compile(Instruction::Literal(low));
+                                self.compile(Instruction::Literal(high));
+                            }
+                            Number::Float(r) => {
+                                self.compile(Instruction::FloatLiteral(r));
+                            }
+                        },
                     }
                 }
-                Fsm::GetVariable => {
-                    // add a value to the dict without a key.
-                    let addr = self
-                        .dictionary
-                        .insert(None, Rc::new(Word::Data(Datum::default())))?;
-
-                    self.dictionary
-                        .insert(Some(word_str.into()), Rc::new(Word::Data(addr as Datum)))?;
-
-                    // Switch back to execution mode
-                    self.fsm = Fsm::Execute;
-                }
             }
         }
 
         Ok(Return::Ok)
     }
 
-    fn run_word(&mut self, word: Rc<Word>) -> Result<(), ContextErr> {
-        match *word {
-            Word::Builtin(ref built_in) => {
-                built_in(self)?;
-            }
-            Word::Data(ref lit) => {
-                self.stack.push(*lit)?;
-            }
-            Word::Custom { ref body } => {
-                // Execute all queued methods
-                for call in body.iter() {
-                    match **call {
-                        _ => {
-                            self.run_word(call.clone())?;
-                        }
-                    }
-                }
+    /// Skips leading whitespace in the input, then returns the characters up to the next whitespace.
+    fn parse_name(&mut self) -> Option<String> {
+        let rest = &self.input[self.input_pos..];
+        let trimmed = rest.trim_start();
+        let start = rest.len() - trimmed.len();
+        let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
 
-                todo!()
-            }
-        }
+        self.input_pos += start + end;
 
-        Ok(())
+        if end == 0 {
+            None
+        } else {
+            Some(trimmed[..end].to_string())
+        }
     }
 
-    fn find_word(&self, word: &str) -> Option<Rc<Word>> {
-        match self.dictionary.get(word.into()) {
-            Some(word) => Some(word.clone()),
-            None => None,
+    /// Parses text up to the delimiter, skipping the single space that separates it from the word before.
+    /// The delimiter is consumed, and the rest of the line is taken if it is missing.
+    fn parse(&mut self, delimiter: char) -> String {
+        let rest = &self.input[self.input_pos..];
+        let rest = match rest.chars().next() {
+            Some(c) if c.is_whitespace() => &rest[c.len_utf8()..],
+            _ => rest,
+        };
+        let start = self.input.len() - rest.len();
+
+        match rest.find(delimiter) {
+            Some(end) => {
+                let text = rest[..end].to_string();
+                self.input_pos = start + end + delimiter.len_utf8();
+                text
+            }
+            None => {
+                let text = rest.trim_end_matches(['\r', '\n']).to_string();
+                self.input_pos = self.input.len();
+                text
+            }
         }
     }
 
-    fn convert_to_number(&self, word: &str) -> Result<Datum, ContextErr> {
-        Ok(word.parse::<Datum>()?)
+    /// Parses a name from the input, returning an error if the line has ended.
+    fn expect_name(&mut self) -> Result<String, ContextErr> {
+        self.parse_name().ok_or(ContextErr::MissingName)
     }
 
-    fn set_primitives(&mut self) -> Result<(), ContextErr> {
-        builtin_word!(self : "does>" => |context| {
-            todo!();
-        });
-
-        builtin_word!(self : "create" => |context| {
-            todo!();
-        });
-
-        builtin_word!(self : "drop" => |context| {
-            context.stack.pop()?;
-            Ok(())
-        });
-
-        builtin_word!(self : "print" => |context| {
-            // Print a value
-            let val = context.stack.pop()?;
-            println!(":: {:?}", val);
-            context.stack.push(val)?;
-            Ok(())
-        });
-
-        builtin_word!(self : "!" => |context| {
-            let addr = context.stack.pop()?;
-            let x = context.stack.pop()?;
-
-            context.dictionary.set_from_addr(addr as usize, Rc::new(Word::Data(x)))?;
+    /// Reads the cell at the given address of the data space.
+    fn fetch_cell(&self, addr: C) -> Result<C, ContextErr> {
+        let bytes = self.data_space.fetch(addr.to_usize(), C::SIZE)?;
+        Ok(C::from_bytes(bytes))
+    }
 
-            Ok(())
-        });
+    /// Writes the cell to the given address of the data space.
+    fn store_cell(&mut self, addr: C, x: C) -> Result<(), ContextErr> {
+        self.data_space.store(addr.to_usize(), &x.to_bytes())?;
+        Ok(())
+    }
 
-        builtin_word!(self : "dict" => |context| {
-            for (i, kv) in context.dictionary.dictionary().iter().enumerate(){
-                println!("{:?}: DICT: {:?}",i, kv);
-            }
-            Ok(())
-        });
+    /// Reads the float at the given address of the data space.
+    fn fetch_float(&self, addr: C) -> Result<f64, ContextErr> {
+        let bytes = self.data_space.fetch(addr.to_usize(), FLOAT_SIZE)?;
+        let mut float = [0; FLOAT_SIZE];
+        float.copy_from_slice(bytes);
 
-        builtin_word!(self : "@" => |context| {
-            // TODO: test
-            // https://forth-standard.org/standard/core/Fetch
-            let a_addr = context.stack.pop()?;
-            let a_addr = a_addr as usize;
-            match  context.dictionary.get_from_addr(a_addr) {
-                Some((key, value)) => {
-                    match **value {
-                        Word::Data(i) => {
-                            context.stack.push(i)?;
-                        },
-                        _ => {
-                            let mut found = false;
-
-                            if let Some(key) = key {
-                                if let Some(addr) = context.dictionary.get_addr(*key){
-                                    found = true;
-                                    context.stack.push(addr as Datum)?;
-                                }
-                            }
+        Ok(f64::from_le_bytes(float))
+    }
 
-                            if !found{
-                                todo!("Attempted to get key that didn't exist.");
-                            }
-                        }
-                    }
+    /// Writes the float to the given address of the data space.
+    fn store_float(&mut self, addr: C, r: f64) -> Result<(), ContextErr> {
+        self.data_space.store(addr.to_usize(), &r.to_le_bytes())?;
+        Ok(())
+    }
 
-                },
-                None => {
-                    return Err(ContextErr::AccessedUndefinedAtAddr(a_addr));
-                }
-            }
+    /// Moves `here` forward to the next cell boundary.
+    fn align(&mut self) -> Result<(), ContextErr> {
+        self.align_to(C::SIZE)
+    }
 
-            Ok(())
-        });
+    /// Moves `here` forward to the next multiple of the alignment.
+    fn align_to(&mut self, alignment: usize) -> Result<(), ContextErr> {
+        let here = self.data_space.here();
+        self.data_space
+            .allot((aligned(here, alignment) - here) as isize)?;
+        Ok(())
+    }
 
-        builtin_word!(self : "-" => |context| {
-            let n1 = context.stack.pop()?;
-            let n2 = context.stack.pop()?;
+    /// Parses a name and defines it as a word pushing the address of the given number of newly reserved cells.
+    fn define_variable(&mut self, cells: usize) -> Result<(), ContextErr> {
+        self.define_space(C::SIZE, cells * C::SIZE)
+    }
 
-            context.stack.push(n1 - n2)?;
+    /// Parses a name and defines it as a word pushing the address of the given number of newly reserved bytes,
+    /// starting at the next multiple of the alignment.
+    fn define_space(&mut self, alignment: usize, bytes: usize) -> Result<(), ContextErr> {
+        let name = self.expect_name()?;
 
-            Ok(())
-        });
+        self.align_to(alignment)?;
+        let addr = self.data_space.here();
+        self.define(&name, Word::Data(C::from_usize(addr)))?;
 
-        builtin_word!(self : "+" => |context| {
-            let n1 = context.stack.pop()?;
-            let n2 = context.stack.pop()?;
-            context.stack.push(n1 + n2)?;
-            Ok(())
-        });
+        self.data_space.allot(bytes as isize)?;
+        Ok(())
+    }
 
-        builtin_word!(self : "*" => |context| {
-            let n1 = context.stack.pop()?;
-            let n2 = context.stack.pop()?;
-            context.stack.push(n1 * n2)?;
-            Ok(())
-        });
+    /// Pops the left and right operands of `-` or `/`, in the order the settings require.
+    fn pop_ordered_operands(&mut self) -> Result<(C, C), ContextErr> {
+        let top = self.stack.pop()?;
+        let second = self.stack.pop()?;
 
-        builtin_word!(self : "/" => |context| {
-            let n1 = context.stack.pop()?;
-            let n2 = context.stack.pop()?;
-            if n2 == 0 {
-                return Err(ContextErr::DivideByZero);
-            }
+        match self.settings.operand_order {
+            OperandOrder::Standard => Ok((second, top)),
+            OperandOrder::Reversed => Ok((top, second)),
+        }
+    }
 
-            context.stack.push(n1 / n2)?;
-            Ok(())
-        });
+    /// Converts the exact result of an arithmetic word to a cell, as the overflow setting requires.
+    fn narrow(&self, n: C::Double) -> Result<C, ContextErr> {
+        match self.settings.overflow {
+            Overflow::Wrapping => Ok(C::wrapping_narrow(n)),
+            Overflow::Checked => C::checked_narrow(n).ok_or(ContextErr::ArithmeticOverflow),
+            Overflow::Saturating => Ok(C::saturating_narrow(n)),
+        }
+    }
 
-        builtin_word!(self : "dup" => |context |{
-            let n = context.stack.pop()?;
-            context.stack.push(n)?;
-            context.stack.push(n)?;
-            Ok(())
-        });
+    /// Pushes the exact result of an arithmetic word, as the overflow setting requires.
+    fn push_exact(&mut self, n: C::Double) -> Result<(), ContextErr> {
+        let n = self.narrow(n)?;
+        self.stack.push(n)?;
+        Ok(())
+    }
 
+    /// Pushes a double as two cells, with the high cell on top.
+    fn push_double(&mut self, d: C::Double) -> Result<(), ContextErr> {
+        let (low, high) = C::split(d);
+        self.stack.push(low)?;
+        self.stack.push(high)?;
         Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Pops a double that takes up the top two cells.
+    fn pop_double(&mut self) -> Result<C::Double, ContextErr> {
+        let high = self.stack.pop()?;
+        let low = self.stack.pop()?;
+        Ok(C::join(low, high))
+    }
 
-    #[test]
-    fn test_div_divides() {
-        let mut f = Context::new(333, 343);
-        f.eval("4 7 /".into()).unwrap();
-        assert_eq!(1, f.stack()[0]);
+    /// Applies whichever of the operations the overflow setting requires to two doubles.
+    fn double_op(
+        &self,
+        d1: C::Double,
+        d2: C::Double,
+        wrapping: fn(C::Double, C::Double) -> C::Double,
+        checked: fn(C::Double, C::Double) -> Option<C::Double>,
+        saturating: fn(C::Double, C::Double) -> C::Double,
+    ) -> Result<C::Double, ContextErr> {
+        match self.settings.overflow {
+            Overflow::Wrapping => Ok(wrapping(d1, d2)),
+            Overflow::Checked => checked(d1, d2).ok_or(ContextErr::ArithmeticOverflow),
+            Overflow::Saturating => Ok(saturating(d1, d2)),
+        }
+    }
 
-        f.reset();
+    fn push_float(&mut self, r: f64) -> Result<(), ContextErr> {
+        self.float_stack.push(r).map_err(ContextErr::FloatStackErr)
+    }
 
-        f.eval("3 -9 /".into()).unwrap();
-        assert_eq!(-3, f.stack()[0]);
+    fn pop_float(&mut self) -> Result<f64, ContextErr> {
+        self.float_stack.pop().map_err(ContextErr::FloatStackErr)
+    }
 
-        f.reset();
+    /// Pops `r2` then `r1` off the float stack, pushing the result of `op(r1, r2)`.
+    fn float_binary_op(&mut self, op: fn(f64, f64) -> f64) -> Result<(), ContextErr> {
+        let r2 = self.pop_float()?;
+        let r1 = self.pop_float()?;
+        self.push_float(op(r1, r2))
+    }
 
-        assert_eq!(
-            ContextErr::DivideByZero,
-            f.eval("0 -9 /".into()).unwrap_err()
-        );
+    /// Pops `r` off the float stack, pushing the result of `op(r)`.
+    fn float_unary_op(&mut self, op: fn(f64) -> f64) -> Result<(), ContextErr> {
+        let r = self.pop_float()?;
+        self.push_float(op(r))
     }
 
-    #[test]
-    fn test_mul_multiplies() {
-        let mut f = Context::new(333, 343);
-        f.eval("4 7 *".into()).unwrap();
-        assert_eq!(28, f.stack()[0]);
+    /// Pops `n2` then `n1`, pushing the result of `op(n1, n2)`.
+    fn binary_op(&mut self, op: fn(C, C) -> C) -> Result<(), ContextErr> {
+        let n2 = self.stack.pop()?;
+        let n1 = self.stack.pop()?;
+        self.stack.push(op(n1, n2))?;
+        Ok(())
+    }
 
-        f.eval("-9 *".into()).unwrap();
-        assert_eq!(-252, f.stack()[0]);
+    /// Pops `n`, pushing the result of `op(n)`.
+    fn unary_op(&mut self, op: fn(C) -> C) -> Result<(), ContextErr> {
+        let n = self.stack.pop()?;
+        self.stack.push(op(n))?;
+        Ok(())
     }
 
-    #[test]
-    fn test_sub_subtracts() {
-        let mut f = Context::new(333, 343);
-        f.eval("1 2 -".into()).unwrap();
-        assert_eq!(1, f.stack()[0]);
+    /// Divides `n1` by `n2`, rounding as the settings require. Returns the remainder and the quotient.
+    fn divide(&self, n1: C::Double, n2: C::Double) -> Result<(C::Double, C::Double), ContextErr> {
+        if n2 == C::Double::ZERO {
+            return Err(ContextErr::DivideByZero);
+        }
 
-        f.eval("-9 -".into()).unwrap();
-        assert_eq!(-10, f.stack()[0]);
-    }
+        let mut quotient = n1.wrapping_div(n2);
+        let mut remainder = n1.wrapping_rem(n2);
 
-    #[test]
-    fn test_plus_adds() {
-        let mut f = Context::new(333, 343);
-        f.eval("1 2 +".into()).unwrap();
-        assert_eq!(3, f.stack()[0]);
+        if self.settings.division == Division::Floored
+            && remainder != C::Double::ZERO
+            && (remainder < C::Double::ZERO) != (n2 < C::Double::ZERO)
+        {
+            quotient = quotient - C::Double::ONE;
+            remainder = remainder + n2;
+        }
 
-        f.eval("1 +".into()).unwrap();
-        assert_eq!(4, f.stack()[0]);
+        Ok((remainder, quotient))
     }
 
-    #[test]
-    fn test_DUP_duplicates_top_of_stack() {
-        let mut f = Context::new(333, 343);
-        f.eval("1 DUP".into()).unwrap();
-        assert_eq!(1, f.stack()[0]);
-        assert_eq!(1, f.stack()[1]);
-    }
+    fn run_word(&mut self, word: Rc<Word<C>>) -> Result<(), ContextErr> {
+        match *word {
+            Word::Builtin(ref built_in) | Word::Immediate(ref built_in) => {
+                built_in(self)?;
+            }
+            Word::Data(ref lit) => {
+                self.stack.push(*lit)?;
+            }
+            Word::Float(r) => {
+                self.push_float(r)?;
+            }
+            Word::Custom { .. } => {
+                self.execute(word, 0)?;
+            }
+            Word::Value(addr) => {
+                let x = self.fetch_cell(C::from_usize(addr))?;
+                self.stack.push(x)?;
+            }
+            Word::Created { addr, ref does } => {
+                self.stack.push(C::from_usize(addr))?;
 
-    #[test]
-    fn test_bye_returns_exist() {
-        assert_eq!(true, false);
+                if let Some((code, ip)) = does {
+                    self.execute(code.clone(), *ip)?;
+                }
+            }
+        }
+
+        Ok(())
     }
 
-    #[test]
-    fn variable() {
-        let mut f = Context::new(333, 343);
+    /// The inner interpreter. Runs the body of a custom word from the given instruction until that word exits.
+    /// Calls to other custom words push a frame onto the return stack instead of recursing, and a call that is
+    /// immediately followed by an `Exit` reuses the current frame.
+    fn execute(&mut self, mut word: Rc<Word<C>>, mut ip: usize) -> Result<(), ContextErr> {
+        let base = self.return_stack.len();
 
-        f.eval("variable balance 123 balance ! balance @".into())
-            .unwrap();
+        loop {
+            let instruction = match *word {
+                Word::Custom { ref body } => body.get(ip).cloned(),
+                _ => None,
+            };
+            ip += 1;
 
-        assert_eq!(f.stack()[0], 123);
+            match instruction.unwrap_or(Instruction::Exit) {
+                Instruction::Call(callee) => match *callee {
+                    Word::Custom { .. } => {
+                        self.push_return(word, ip)?;
+                        word = callee;
+                        ip = 0;
+                    }
+                    Word::Created {
+                        addr,
+                        does: Some((ref code, start)),
+                    } => {
+                        self.stack.push(C::from_usize(addr))?;
+                        self.push_return(word, ip)?;
+                        word = code.clone();
+                        ip = start;
+                    }
+                    _ => {
+                        self.run_word(callee)?;
+                    }
+                },
+                Instruction::Recurse => {
+                    self.push_return(word.clone(), ip)?;
+                    ip = 0;
+                }
+                Instruction::Literal(lit) => {
+                    self.stack.push(lit)?;
+                }
+                Instruction::FloatLiteral(r) => {
+                    self.push_float(r)?;
+                }
+                Instruction::Branch(target) => {
+                    ip = target;
+                }
+                Instruction::BranchIfZero(target) => {
+                    if self.stack.pop()? == C::ZERO {
+                        ip = target;
+                    }
+                }
+                Instruction::Does => {
+                    self.attach_does(word.clone(), ip)?;
+
+                    match self.return_to_caller(base)? {
+                        Some((caller, resume)) => {
+                            word = caller;
+                            ip = resume;
+                        }
+                        None => return Ok(()),
+                    }
+                }
+                Instruction::Exit => match self.return_to_caller(base)? {
+                    Some((caller, resume)) => {
+                        word = caller;
+                        ip = resume;
+                    }
+                    None => return Ok(()),
+                },
+                Instruction::Do => {
+                    let index = self.stack.pop()?;
+                    let limit = self.stack.pop()?;
+                    self.push_loop(index, limit)?;
+                }
+                Instruction::QuestionDo(target) => {
+                    let index = self.stack.pop()?;
+                    let limit = self.stack.pop()?;
+
+                    if index == limit {
+                        ip = target;
+                    } else {
+                        self.push_loop(index, limit)?;
+                    }
+                }
+                Instruction::Loop(target) => {
+                    if self.step_loop(C::from_usize(1))? {
+                        ip = target;
+                    }
+                }
+                Instruction::PlusLoop(target) => {
+                    let step = self.stack.pop()?;
+
+                    if self.step_loop(step)? {
+                        ip = target;
+                    }
+                }
+                Instruction::Leave(target) => {
+                    self.pop_loop()?;
+                    ip = target;
+                }
+            }
+        }
+    }
+
+    fn push_loop(&mut self, index: C, limit: C) -> Result<(), ContextErr> {
+        self.return_stack
+            .push(Frame::Loop { index, limit })
+            .map_err(ContextErr::ReturnStackErr)
+    }
+
+    /// Pops the parameters of the current loop off the return stack.
+    fn pop_loop(&mut self) -> Result<(C, C), ContextErr> {
+        match self.pop_return()? {
+            Frame::Loop { index, limit } => Ok((index, limit)),
+            _ => Err(ContextErr::ReturnStackMismatch),
+        }
+    }
+
+    /// Adds the step to the current loop index, returning whether the loop should run again.
+    /// A finished loop is removed from the return stack.
+    fn step_loop(&mut self, step: C) -> Result<bool, ContextErr> {
+        let (index, limit) = self.pop_loop()?;
+        let before = index.wrapping_sub(limit);
+        let after = before.wrapping_add(step);
+
+        let crossed_limit = if step >= C::ZERO {
+            before < C::ZERO && after >= C::ZERO
+        } else {
+            before >= C::ZERO && after < C::ZERO
+        };
+
+        if crossed_limit {
+            return Ok(false);
+        }
+
+        self.push_loop(index.wrapping_add(step), limit)?;
+        Ok(true)
+    }
+
+    /// Returns the index of a loop on the return stack, where a depth of 0 is the innermost loop.
+    fn loop_index(&self, depth: usize) -> Result<C, ContextErr> {
+        match self.return_stack.peek(depth) {
+            Ok(Frame::Loop { index, .. }) => Ok(*index),
+            Ok(_) => Err(ContextErr::ReturnStackMismatch),
+            Err(e) => Err(ContextErr::ReturnStackErr(e)),
+        }
+    }
+
+    /// Pops where to resume execution off the return stack, or returns `None` if the return stack is back to the
+    /// given depth.
+    fn return_to_caller(&mut self, base: usize) -> Result<Option<Resume<C>>, ContextErr> {
+        if self.return_stack.len() == base {
+            return Ok(None);
+        }
+
+        match self.pop_return()? {
+            Frame::Return { word, ip } => Ok(Some((word, ip))),
+            _ => Err(ContextErr::ReturnStackMismatch),
+        }
+    }
+
+    /// Makes the most recently created word run the code starting at the given instruction.
+    fn attach_does(&mut self, code: Rc<Word<C>>, ip: usize) -> Result<(), ContextErr> {
+        let name = self.last_created.ok_or(ContextErr::DoesWithoutCreate)?;
+        let word_addr = self
+            .dictionary
+            .get_addr(name)
+            .ok_or(ContextErr::DoesWithoutCreate)?;
+
+        let addr = match self.dictionary.get_from_addr(word_addr) {
+            Some((_, word)) => match **word {
+                Word::Created { addr, .. } => addr,
+                _ => return Err(ContextErr::DoesWithoutCreate),
+            },
+            None => return Err(ContextErr::DoesWithoutCreate),
+        };
+
+        let does = Some((code, ip));
+        self.dictionary
+            .set_from_addr(word_addr, Rc::new(Word::Created { addr, does }))?;
+
+        Ok(())
+    }
+
+    /// Saves where to resume execution, unless the next instruction would just exit.
+    fn push_return(&mut self, word: Rc<Word<C>>, ip: usize) -> Result<(), ContextErr> {
+        let is_tail_call = match *word {
+            Word::Custom { ref body } => matches!(body.get(ip), None | Some(Instruction::Exit)),
+            _ => true,
+        };
+
+        if !is_tail_call {
+            self.return_stack
+                .push(Frame::Return { word, ip })
+                .map_err(ContextErr::ReturnStackErr)?;
+        }
+
+        Ok(())
+    }
+
+    fn pop_return(&mut self) -> Result<Frame<C>, ContextErr> {
+        self.return_stack.pop().map_err(ContextErr::ReturnStackErr)
+    }
+
+    /// Returns an error naming the word if a definition is not being compiled.
+    fn ensure_compiling(&self, word: &str) -> Result<(), ContextErr> {
+        match self.mode {
+            Mode::Compiling => Ok(()),
+            Mode::Interpreting => Err(ContextErr::CompileOnly(word.into())),
+        }
+    }
+
+    /// Appends an instruction to the definition being compiled.
+    fn compile(&mut self, instruction: Instruction<C>) {
+        if let Some(definition) = &mut self.definition {
+            definition.body.push(instruction);
+        }
+    }
+
+    /// Returns the index the next compiled instruction will have.
+    fn next_instruction(&self) -> usize {
+        match &self.definition {
+            Some(definition) => definition.body.len(),
+            None => 0,
+        }
+    }
+
+    /// Compiles a forward branch with an unknown target, leaving it on the control-flow stack.
+    fn compile_forward(&mut self, instruction: Instruction<C>) -> Result<(), ContextErr> {
+        let orig = self.next_instruction();
+        self.compile(instruction);
+        self.control_flow.push(Control::Orig(orig))?;
+        Ok(())
+    }
+
+    /// Pops a forward branch off the control-flow stack and points it at the next compiled instruction.
+    fn resolve_forward(&mut self, word: &str) -> Result<(), ContextErr> {
+        let orig = self.pop_orig(word)?;
+        let target = self.next_instruction();
+        self.patch_branch(orig, target);
+        Ok(())
+    }
+
+    /// Pops a forward branch off the control-flow stack, returning an error naming the word if there isn't one.
+    fn pop_orig(&mut self, word: &str) -> Result<usize, ContextErr> {
+        match self.control_flow.pop() {
+            Ok(Control::Orig(orig)) => Ok(orig),
+            _ => Err(ContextErr::UnbalancedControlFlow(word.into())),
+        }
+    }
+
+    /// Pops the target of a backward branch off the control-flow stack, returning an error naming the word if there isn't one.
+    fn pop_dest(&mut self, word: &str) -> Result<usize, ContextErr> {
+        match self.control_flow.pop() {
+            Ok(Control::Dest(dest)) => Ok(dest),
+            _ => Err(ContextErr::UnbalancedControlFlow(word.into())),
+        }
+    }
+
+    /// Starts compiling a counted loop, after its `Do` or `QuestionDo` instruction has been compiled.
+    fn begin_loop(&mut self, leaves: Vec<usize>) -> Result<(), ContextErr> {
+        let start = self.next_instruction();
+        self.control_flow.push(Control::Do(start))?;
+        self.leaves.push(leaves);
+        Ok(())
+    }
+
+    /// Finishes compiling a counted loop, pointing every `leave` in it past the end of the loop.
+    fn end_loop(
+        &mut self,
+        word: &str,
+        instruction: fn(usize) -> Instruction<C>,
+    ) -> Result<(), ContextErr> {
+        let start = match self.control_flow.pop() {
+            Ok(Control::Do(start)) => start,
+            _ => return Err(ContextErr::UnbalancedControlFlow(word.into())),
+        };
+
+        self.compile(instruction(start));
+        let target = self.next_instruction();
+
+        for orig in self.leaves.pop().unwrap_or_default() {
+            self.patch_branch(orig, target);
+        }
+
+        Ok(())
+    }
+
+    /// Sets the target of the branch at the given index.
+    fn patch_branch(&mut self, index: usize, target: usize) {
+        if let Some(definition) = &mut self.definition {
+            match definition.body.get_mut(index) {
+                Some(Instruction::Branch(t))
+                | Some(Instruction::BranchIfZero(t))
+                | Some(Instruction::QuestionDo(t))
+                | Some(Instruction::Leave(t)) => {
+                    *t = target;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Stops compiling, returning the unfinished definition if there was one.
+    fn end_definition(&mut self) -> Option<Definition<C>> {
+        self.mode = Mode::Interpreting;
+        self.control_flow.clear();
+        self.leaves.clear();
+        self.definition.take()
+    }
+
+    /// Folds the case of a name unless the settings ask for strict matching.
+    fn fold_case(&self, name: &str) -> String {
+        match self.settings.case {
+            Case::Insensitive => name.to_lowercase(),
+            Case::Sensitive => name.to_string(),
+        }
+    }
+
+    /// Returns the dictionary key for a name, interning it if needed.
+    fn key(&mut self, name: &str) -> Id {
+        let name = self.fold_case(name);
+        self.names.intern(&name)
+    }
+
+    /// Adds the word to the dictionary under the name, returning its key.
+    fn define(&mut self, name: &str, word: Word<C>) -> Result<Id, ContextErr> {
+        let origin = self.data_space.here();
+        self.insert_word(name, word, origin)
+    }
+
+    /// Adds the word to the dictionary under the name, remembering how the name was spelled and where `here` was
+    /// before the word was defined.
+    fn insert_word(
+        &mut self,
+        name: &str,
+        word: Word<C>,
+        origin: data_space::Addr,
+    ) -> Result<Id, ContextErr> {
+        let key = self.key(name);
+        let spelling = self.names.intern(name);
+
+        self.dictionary.insert(Some(key), Rc::new(word))?;
+        self.spellings.push(spelling);
+        self.origins.push(origin);
+        Ok(key)
+    }
+
+    /// Returns the name of the dictionary entry at the address, spelled as it was defined.
+    fn spelling(&self, addr: dictionary::Addr) -> &str {
+        self.names.name(self.spellings[addr])
+    }
+
+    /// Removes the dictionary entry at the address and every entry after it, moving `here` back to where it was
+    /// before the entry was defined. Definitions the removed entries shadowed are found again.
+    fn forget_from(&mut self, addr: dictionary::Addr) -> Result<(), ContextErr> {
+        let origin = match self.origins.get(addr) {
+            Some(origin) => *origin,
+            None => return Ok(()),
+        };
+
+        self.dictionary.truncate(addr);
+        self.spellings.truncate(addr);
+        self.origins.truncate(addr);
+        self.last_created = None;
+
+        let here = self.data_space.here();
+        self.data_space.allot(origin as isize - here as isize)?;
+        Ok(())
+    }
+
+    fn find_word(&self, word: &str) -> Option<Rc<Word<C>>> {
+        // A name that was never interned cannot have been defined.
+        let key = self.names.get(&self.fold_case(word))?;
+        self.dictionary.get(key).cloned()
+    }
+
+    /// Parses a number in the current base, unless it starts with a prefix naming its own base or is a character
+    /// literal such as `'A'`. Floats are only recognised while the base is decimal.
+    fn convert_to_number(&self, word: &str) -> Result<Number<C>, ContextErr> {
+        if let Some(c) = parse_char(word) {
+            return Ok(Number::Single(C::from_usize(c as usize)));
+        }
+
+        let (radix, digits) = match word.chars().next() {
+            Some('$') => (16, &word[1..]),
+            Some('#') => (10, &word[1..]),
+            Some('%') => (2, &word[1..]),
+            _ => (self.base()?, word),
+        };
+
+        if radix == 10 && digits == word {
+            if let Some(r) = parse_float(word) {
+                return Ok(Number::Float(r));
+            }
+        }
+
+        match digits.strip_suffix('.') {
+            Some(double) => Ok(Number::Double(C::Double::from_str_radix(double, radix)?)),
+            None => Ok(Number::Single(C::from_str_radix(digits, radix)?)),
+        }
+    }
+
+    /// Appends the text to the output.
+    fn write(&mut self, text: &str) {
+        self.output.push_str(text);
+    }
+
+    /// Appends the number of spaces to the output, up to `MAX_SPACES`.
+    fn write_spaces(&mut self, n: usize) {
+        let n = n.min(MAX_SPACES);
+        self.output.extend(std::iter::repeat_n(' ', n));
+    }
+
+    /// Pushes the value, or compiles it as a literal if a definition is being compiled.
+    fn literal(&mut self, x: C) -> Result<(), ContextErr> {
+        match self.mode {
+            Mode::Interpreting => self.stack.push(x)?,
+            Mode::Compiling => self.compile(Instruction::Literal(x)),
+        }
+
+        Ok(())
+    }
+
+    /// Adds a character to the start of the pictured numeric output.
+    fn hold(&mut self, c: u8) -> Result<(), ContextErr> {
+        if self.hold.len() == pictured_size::<C>() {
+            return Err(ContextErr::PicturedOutputOverflow);
+        }
+
+        self.hold.insert(0, c);
+        Ok(())
+    }
+
+    /// Divides the unsigned double on top of the stack by the base, holding the remainder as a digit.
+    fn hold_digit(&mut self) -> Result<C::Double, ContextErr> {
+        let ud = self.pop_double()?;
+        let (ud, digit) = ud.unsigned_div_rem(self.base()?);
+        self.hold(digit_char(digit))?;
+        self.push_double(ud)?;
+        Ok(ud)
+    }
+
+    /// Writes the number in the current base, right aligned in a field of the given width.
+    fn write_number(&mut self, n: C::Double, width: C) -> Result<(), ContextErr> {
+        let digits = n.to_str_radix(self.base()?);
+        let width = width.to_isize().max(0) as usize;
+        self.write_spaces(width.saturating_sub(digits.len()));
+        self.write(&digits);
+        Ok(())
+    }
+
+    /// Returns the radix held by `base`, which numbers are parsed and displayed in.
+    fn base(&self) -> Result<u32, ContextErr> {
+        let base = self.fetch_cell(C::from_usize(self.base))?;
+
+        match base.to_usize() {
+            base @ 2..=36 => Ok(base as u32),
+            _ => Err(ContextErr::InvalidBase),
+        }
+    }
+
+    fn set_base(&mut self, base: usize) -> Result<(), ContextErr> {
+        self.store_cell(C::from_usize(self.base), C::from_usize(base))
+    }
+
+    fn set_primitives(&mut self) -> Result<(), ContextErr> {
+        // https://forth-standard.org/standard/core/BASE
+        self.base = self.data_space.append(&C::from_usize(10).to_bytes())?;
+        self.define("base", Word::Data(C::from_usize(self.base)))?;
+        self.pictured = self.data_space.here();
+        self.data_space.allot(pictured_size::<C>() as isize)?;
+        for i in 0..self.strings.len() {
+            self.strings[i] = self.data_space.here();
+            self.data_space.allot(STRING_BUFFER_SIZE as isize)?;
+        }
+        self.align()?;
+
+        builtin_word!(self : "decimal" => |context| {
+            // https://forth-standard.org/standard/core/DECIMAL
+            context.set_base(10)
+        });
+
+        builtin_word!(self : "hex" => |context| {
+            // https://forth-standard.org/standard/core/HEX
+            context.set_base(16)
+        });
+
+        builtin_word!(self : "binary" => |context| {
+            // Not in the standard, but common alongside `hex` and `decimal`.
+            context.set_base(2)
+        });
+
+        builtin_word!(self : ";", immediate => |context| {
+            // https://forth-standard.org/standard/core/Semi
+            if !context.control_flow.is_empty() {
+                return Err(ContextErr::UnbalancedControlFlow(";".into()));
+            }
+
+            context.compile(Instruction::Exit);
+
+            match context.end_definition() {
+                Some(Definition { name, body, origin }) => {
+                    context.insert_word(&name, Word::Custom { body }, origin)?;
+                    Ok(())
+                }
+                None => Err(ContextErr::CompileOnly(";".into())),
+            }
+        });
+
+        builtin_word!(self : "exit", immediate => |context| {
+            // https://forth-standard.org/standard/core/EXIT
+            context.ensure_compiling("exit")?;
+            context.compile(Instruction::Exit);
+            Ok(())
+        });
+
+        builtin_word!(self : "recurse", immediate => |context| {
+            // https://forth-standard.org/standard/core/RECURSE
+            context.ensure_compiling("recurse")?;
+            context.compile(Instruction::Recurse);
+            Ok(())
+        });
+
+        builtin_word!(self : "if", immediate => |context| {
+            // https://forth-standard.org/standard/core/IF
+            context.ensure_compiling("if")?;
+            context.compile_forward(Instruction::BranchIfZero(0))
+        });
+
+        builtin_word!(self : "else", immediate => |context| {
+            // https://forth-standard.org/standard/core/ELSE
+            context.ensure_compiling("else")?;
+            let orig = context.pop_orig("else")?;
+
+            context.compile_forward(Instruction::Branch(0))?;
+            let target = context.next_instruction();
+            context.patch_branch(orig, target);
+            Ok(())
+        });
+
+        builtin_word!(self : "then", immediate => |context| {
+            // https://forth-standard.org/standard/core/THEN
+            context.ensure_compiling("then")?;
+            context.resolve_forward("then")
+        });
+
+        builtin_word!(self : "begin", immediate => |context| {
+            // https://forth-standard.org/standard/core/BEGIN
+            context.ensure_compiling("begin")?;
+            let dest = context.next_instruction();
+            context.control_flow.push(Control::Dest(dest))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "until", immediate => |context| {
+            // https://forth-standard.org/standard/core/UNTIL
+            context.ensure_compiling("until")?;
+            let dest = context.pop_dest("until")?;
+            context.compile(Instruction::BranchIfZero(dest));
+            Ok(())
+        });
+
+        builtin_word!(self : "again", immediate => |context| {
+            // https://forth-standard.org/standard/core/AGAIN
+            context.ensure_compiling("again")?;
+            let dest = context.pop_dest("again")?;
+            context.compile(Instruction::Branch(dest));
+            Ok(())
+        });
+
+        builtin_word!(self : "while", immediate => |context| {
+            // https://forth-standard.org/standard/core/WHILE
+            context.ensure_compiling("while")?;
+            let dest = context.pop_dest("while")?;
+            context.compile_forward(Instruction::BranchIfZero(0))?;
+            context.control_flow.push(Control::Dest(dest))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "repeat", immediate => |context| {
+            // https://forth-standard.org/standard/core/REPEAT
+            context.ensure_compiling("repeat")?;
+            let dest = context.pop_dest("repeat")?;
+            context.compile(Instruction::Branch(dest));
+            context.resolve_forward("repeat")
+        });
+
+        builtin_word!(self : "do", immediate => |context| {
+            // https://forth-standard.org/standard/core/DO
+            context.ensure_compiling("do")?;
+            context.compile(Instruction::Do);
+            context.begin_loop(vec![])
+        });
+
+        builtin_word!(self : "?do", immediate => |context| {
+            // https://forth-standard.org/standard/core/qDO
+            context.ensure_compiling("?do")?;
+            let orig = context.next_instruction();
+            context.compile(Instruction::QuestionDo(0));
+            context.begin_loop(vec![orig])
+        });
+
+        builtin_word!(self : "loop", immediate => |context| {
+            // https://forth-standard.org/standard/core/LOOP
+            context.ensure_compiling("loop")?;
+            context.end_loop("loop", Instruction::Loop)
+        });
+
+        builtin_word!(self : "+loop", immediate => |context| {
+            // https://forth-standard.org/standard/core/PlusLOOP
+            context.ensure_compiling("+loop")?;
+            context.end_loop("+loop", Instruction::PlusLoop)
+        });
+
+        builtin_word!(self : "leave", immediate => |context| {
+            // https://forth-standard.org/standard/core/LEAVE
+            context.ensure_compiling("leave")?;
+            let orig = context.next_instruction();
+
+            match context.leaves.last_mut() {
+                Some(leaves) => leaves.push(orig),
+                None => return Err(ContextErr::UnbalancedControlFlow("leave".into())),
+            }
+
+            context.compile(Instruction::Leave(0));
+            Ok(())
+        });
+
+        builtin_word!(self : "unloop" => |context| {
+            // https://forth-standard.org/standard/core/UNLOOP
+            context.pop_loop()?;
+            Ok(())
+        });
+
+        builtin_word!(self : "i" => |context| {
+            // https://forth-standard.org/standard/core/I
+            let index = context.loop_index(0)?;
+            context.stack.push(index)?;
+            Ok(())
+        });
+
+        builtin_word!(self : "j" => |context| {
+            // https://forth-standard.org/standard/core/J
+            let index = context.loop_index(1)?;
+            context.stack.push(index)?;
+            Ok(())
+        });
+
+        builtin_word!(self : ">r" => |context| {
+            // https://forth-standard.org/standard/core/toR
+            let x = context.stack.pop()?;
+            context
+                .return_stack
+                .push(Frame::Data(x))
+                .map_err(ContextErr::ReturnStackErr)?;
+            Ok(())
+        });
+
+        builtin_word!(self : "r>" => |context| {
+            // https://forth-standard.org/standard/core/Rfrom
+            match context.pop_return()? {
+                Frame::Data(x) => {
+                    context.stack.push(x)?;
+                    Ok(())
+                }
+                _ => Err(ContextErr::ReturnStackMismatch),
+            }
+        });
+
+        builtin_word!(self : "r@" => |context| {
+            // https://forth-standard.org/standard/core/RFetch
+            match context.return_stack.peek(0) {
+                Ok(Frame::Data(x)) => {
+                    let x = *x;
+                    context.stack.push(x)?;
+                    Ok(())
+                }
+                Ok(_) => Err(ContextErr::ReturnStackMismatch),
+                Err(e) => Err(ContextErr::ReturnStackErr(e)),
+            }
+        });
+
+        builtin_word!(self : ":", immediate => |context| {
+            // https://forth-standard.org/standard/core/Colon
+            if context.mode == Mode::Compiling {
+                return Err(ContextErr::NestedDefinition);
+            }
+
+            let name = context.expect_name()?;
+            context.definition = Some(Definition {
+                name,
+                body: vec![],
+                origin: context.data_space.here(),
+            });
+            context.mode = Mode::Compiling;
+
+            Ok(())
+        });
+
+        builtin_word!(self : "variable" => |context| {
+            // https://forth-standard.org/standard/core/VARIABLE
+            context.define_variable(1)
+        });
+
+        builtin_word!(self : "var" => |context| {
+            // The original spelling of `variable`, kept for older scripts.
+            context.define_variable(1)
+        });
+
+        builtin_word!(self : "2variable" => |context| {
+            // https://forth-standard.org/standard/double/TwoVARIABLE
+            context.define_variable(2)
+        });
+
+        builtin_word!(self : "constant" => |context| {
+            // https://forth-standard.org/standard/core/CONSTANT
+            let name = context.expect_name()?;
+            let x = context.stack.pop()?;
+            context.define(&name, Word::Data(x))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "2constant" => |context| {
+            // https://forth-standard.org/standard/double/TwoCONSTANT
+            let name = context.expect_name()?;
+            let x2 = context.stack.pop()?;
+            let x1 = context.stack.pop()?;
+            let body = vec![
+                Instruction::Literal(x1),
+                Instruction::Literal(x2),
+                Instruction::Exit,
+            ];
+            context.define(&name, Word::Custom { body })?;
+            Ok(())
+        });
+
+        builtin_word!(self : "value" => |context| {
+            // https://forth-standard.org/standard/core/VALUE
+            let name = context.expect_name()?;
+            let x = context.stack.pop()?;
+
+            context.align()?;
+            let addr = context.data_space.here();
+            context.define(&name, Word::Value(addr))?;
+
+            context.data_space.append(&x.to_bytes())?;
+            Ok(())
+        });
+
+        builtin_word!(self : "to", immediate => |context| {
+            // https://forth-standard.org/standard/core/TO
+            let name = context.expect_name()?;
+            let addr = match context.find_word(&name).as_deref() {
+                Some(Word::Value(addr)) => C::from_usize(*addr),
+                _ => return Err(ContextErr::NotAValue(name.as_str().into())),
+            };
+
+            match context.mode {
+                Mode::Interpreting => {
+                    let x = context.stack.pop()?;
+                    context.store_cell(addr, x)
+                }
+                Mode::Compiling => {
+                    let store: Procedure<C> = Box::new(move |context| {
+                        let x = context.stack.pop()?;
+                        context.store_cell(addr, x)
+                    });
+                    context.compile(Instruction::Call(Rc::new(Word::Builtin(store))));
+                    Ok(())
+                }
+            }
+        });
+
+        builtin_word!(self : "does>", immediate => |context| {
+            // https://forth-standard.org/standard/core/DOES
+            context.ensure_compiling("does>")?;
+            context.compile(Instruction::Does);
+            Ok(())
+        });
+
+        builtin_word!(self : "create" => |context| {
+            // https://forth-standard.org/standard/core/CREATE
+            let name = context.expect_name()?;
+
+            context.align()?;
+            let addr = context.data_space.here();
+
+            let name = context.define(&name, Word::Created { addr, does: None })?;
+            context.last_created = Some(name);
+
+            Ok(())
+        });
+
+        builtin_word!(self : "drop" => |context| {
+            context.stack.pop()?;
+            Ok(())
+        });
+
+        builtin_word!(self : "print" => |context| {
+            // Print a value
+            let val = context.stack.pop()?;
+            context.write(&format!(":: {:?}\n", val));
+            context.stack.push(val)?;
+            Ok(())
+        });
+
+        builtin_word!(self : "!" => |context| {
+            // https://forth-standard.org/standard/core/Store
+            let addr = context.stack.pop()?;
+            let x = context.stack.pop()?;
+            context.store_cell(addr, x)
+        });
+
+        builtin_word!(self : "c!" => |context| {
+            // https://forth-standard.org/standard/core/CStore
+            let addr = context.stack.pop()?;
+            let c = context.stack.pop()?;
+            context.data_space.store(addr.to_usize(), &[c.to_usize() as u8])?;
+            Ok(())
+        });
+
+        builtin_word!(self : "c@" => |context| {
+            // https://forth-standard.org/standard/core/CFetch
+            let addr = context.stack.pop()?;
+            let c = context.data_space.fetch(addr.to_usize(), 1)?[0];
+            context.stack.push(C::from_usize(c as usize))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "here" => |context| {
+            // https://forth-standard.org/standard/core/HERE
+            let here = context.data_space.here();
+            context.stack.push(C::from_usize(here))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "allot" => |context| {
+            // https://forth-standard.org/standard/core/ALLOT
+            let n = context.stack.pop()?;
+            context.data_space.allot(n.to_isize())?;
+            Ok(())
+        });
+
+        builtin_word!(self : "," => |context| {
+            // https://forth-standard.org/standard/core/Comma
+            let x = context.stack.pop()?;
+            context.data_space.append(&x.to_bytes())?;
+            Ok(())
+        });
+
+        builtin_word!(self : "c," => |context| {
+            // https://forth-standard.org/standard/core/CComma
+            let c = context.stack.pop()?;
+            context.data_space.append(&[c.to_usize() as u8])?;
+            Ok(())
+        });
+
+        builtin_word!(self : "align" => |context| {
+            // https://forth-standard.org/standard/core/ALIGN
+            context.align()
+        });
+
+        builtin_word!(self : "aligned" => |context| {
+            // https://forth-standard.org/standard/core/ALIGNED
+            let addr = context.stack.pop()?;
+            context.stack.push(C::from_usize(aligned(addr.to_usize(), C::SIZE)))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "cells" => |context| {
+            // https://forth-standard.org/standard/core/CELLS
+            let n = context.stack.pop()?;
+            context.stack.push(n.wrapping_mul(C::from_usize(C::SIZE)))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "cell+" => |context| {
+            // https://forth-standard.org/standard/core/CELLPlus
+            let addr = context.stack.pop()?;
+            context.stack.push(addr.wrapping_add(C::from_usize(C::SIZE)))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "dict" => |context| {
+            let mut listing = String::new();
+            for (i, (key, word)) in context.dictionary.dictionary().iter().enumerate(){
+                let name = key.map(|_| context.spelling(i));
+                listing.push_str(&format!("{:?}: DICT: {:?}\n",i, (name, word)));
+            }
+            context.write(&listing);
+            Ok(())
+        });
+
+        builtin_word!(self : "marker" => |context| {
+            // https://forth-standard.org/standard/core/MARKER
+            let name = context.expect_name()?;
+            let addr = context.dictionary.dictionary().len();
+
+            let restore: Procedure<C> = Box::new(move |context| context.forget_from(addr));
+            context.define(&name, Word::Builtin(restore))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "forget" => |context| {
+            // https://forth-standard.org/standard/tools/FORGET
+            let name = context.expect_name()?;
+            let addr = context
+                .names
+                .get(&context.fold_case(&name))
+                .and_then(|key| context.dictionary.get_addr(key))
+                .ok_or_else(|| ContextErr::UndefinedWord(name.clone()))?;
+
+            if addr < context.fence {
+                return Err(ContextErr::Protected(name));
+            }
+
+            context.forget_from(addr)
+        });
+
+        builtin_word!(self : "words" => |context| {
+            // https://forth-standard.org/standard/tools/WORDS
+            let names: Vec<&str> = context
+                .dictionary
+                .dictionary()
+                .iter()
+                .enumerate()
+                .rev()
+                .filter_map(|(addr, (key, _))| key.map(|_| context.spelling(addr)))
+                .collect();
+            let listing = names.join(" ");
+            context.write(&listing);
+            Ok(())
+        });
+
+        builtin_word!(self : "@" => |context| {
+            // https://forth-standard.org/standard/core/Fetch
+            let addr = context.stack.pop()?;
+            let x = context.fetch_cell(addr)?;
+            context.stack.push(x)?;
+            Ok(())
+        });
+
+        builtin_word!(self : "-" => |context| {
+            // https://forth-standard.org/standard/core/Minus
+            let (n1, n2) = context.pop_ordered_operands()?;
+
+            context.push_exact(n1.to_double() - n2.to_double())?;
+
+            Ok(())
+        });
+
+        builtin_word!(self : "+" => |context| {
+            let n1 = context.stack.pop()?;
+            let n2 = context.stack.pop()?;
+            context.push_exact(n1.to_double() + n2.to_double())?;
+            Ok(())
+        });
+
+        builtin_word!(self : "*" => |context| {
+            let n1 = context.stack.pop()?;
+            let n2 = context.stack.pop()?;
+            context.push_exact(n1.to_double() * n2.to_double())?;
+            Ok(())
+        });
+
+        builtin_word!(self : "/" => |context| {
+            // https://forth-standard.org/standard/core/Div
+            let (n1, n2) = context.pop_ordered_operands()?;
+            let (_, quotient) = context.divide(n1.to_double(), n2.to_double())?;
+
+            context.push_exact(quotient)?;
+            Ok(())
+        });
+
+        builtin_word!(self : "mod" => |context| {
+            // https://forth-standard.org/standard/core/MOD
+            let n2 = context.stack.pop()?;
+            let n1 = context.stack.pop()?;
+            let (remainder, _) = context.divide(n1.to_double(), n2.to_double())?;
+
+            context.push_exact(remainder)?;
+            Ok(())
+        });
+
+        builtin_word!(self : "/mod" => |context| {
+            // https://forth-standard.org/standard/core/DivMOD
+            let n2 = context.stack.pop()?;
+            let n1 = context.stack.pop()?;
+            let (remainder, quotient) = context.divide(n1.to_double(), n2.to_double())?;
+            let quotient = context.narrow(quotient)?;
+
+            context.push_exact(remainder)?;
+            context.stack.push(quotient)?;
+            Ok(())
+        });
+
+        builtin_word!(self : "*/" => |context| {
+            // https://forth-standard.org/standard/core/TimesDiv
+            let n3 = context.stack.pop()?;
+            let n2 = context.stack.pop()?;
+            let n1 = context.stack.pop()?;
+            let product = n1.to_double() * n2.to_double();
+            let (_, quotient) = context.divide(product, n3.to_double())?;
+
+            context.push_exact(quotient)?;
+            Ok(())
+        });
+
+        builtin_word!(self : "*/mod" => |context| {
+            // https://forth-standard.org/standard/core/TimesDivMOD
+            let n3 = context.stack.pop()?;
+            let n2 = context.stack.pop()?;
+            let n1 = context.stack.pop()?;
+            let product = n1.to_double() * n2.to_double();
+            let (remainder, quotient) = context.divide(product, n3.to_double())?;
+            let quotient = context.narrow(quotient)?;
+
+            context.push_exact(remainder)?;
+            context.stack.push(quotient)?;
+            Ok(())
+        });
+
+        builtin_word!(self : "negate" => |context| {
+            // https://forth-standard.org/standard/core/NEGATE
+            let n = context.stack.pop()?;
+            context.push_exact(-n.to_double())
+        });
+
+        builtin_word!(self : "abs" => |context| {
+            // https://forth-standard.org/standard/core/ABS
+            let n = context.stack.pop()?;
+            context.push_exact(n.to_double().abs())
+        });
+
+        builtin_word!(self : "min" => |context| {
+            // https://forth-standard.org/standard/core/MIN
+            context.binary_op(Ord::min)
+        });
+
+        builtin_word!(self : "max" => |context| {
+            // https://forth-standard.org/standard/core/MAX
+            context.binary_op(Ord::max)
+        });
+
+        builtin_word!(self : "1+" => |context| {
+            // https://forth-standard.org/standard/core/OnePlus
+            let n = context.stack.pop()?;
+            context.push_exact(n.to_double() + C::Double::ONE)
+        });
+
+        builtin_word!(self : "1-" => |context| {
+            // https://forth-standard.org/standard/core/OneMinus
+            let n = context.stack.pop()?;
+            context.push_exact(n.to_double() - C::Double::ONE)
+        });
+
+        builtin_word!(self : "s>d" => |context| {
+            // https://forth-standard.org/standard/core/StoD
+            let n = context.stack.pop()?;
+            context.push_double(n.to_double())
+        });
+
+        builtin_word!(self : "d+" => |context| {
+            // https://forth-standard.org/standard/double/DPlus
+            let d2 = context.pop_double()?;
+            let d1 = context.pop_double()?;
+            let sum = context.double_op(
+                d1,
+                d2,
+                C::Double::wrapping_add,
+                C::Double::checked_add,
+                C::Double::saturating_add,
+            )?;
+            context.push_double(sum)
+        });
+
+        builtin_word!(self : "d-" => |context| {
+            // https://forth-standard.org/standard/double/DMinus
+            let d2 = context.pop_double()?;
+            let d1 = context.pop_double()?;
+            let difference = context.double_op(
+                d1,
+                d2,
+                C::Double::wrapping_sub,
+                C::Double::checked_sub,
+                C::Double::saturating_sub,
+            )?;
+            context.push_double(difference)
+        });
+
+        builtin_word!(self : "m*" => |context| {
+            // https://forth-standard.org/standard/core/MTimes
+            let n2 = context.stack.pop()?;
+            let n1 = context.stack.pop()?;
+            context.push_double(n1.to_double() * n2.to_double())
+        });
+
+        builtin_word!(self : "um*" => |context| {
+            // https://forth-standard.org/standard/core/UMTimes
+            let u2 = context.stack.pop()?;
+            let u1 = context.stack.pop()?;
+            context.push_double(u1.unsigned_mul(u2))
+        });
+
+        builtin_word!(self : "um/mod" => |context| {
+            // https://forth-standard.org/standard/core/UMDivMOD
+            let u1 = context.stack.pop()?;
+            let ud = context.pop_double()?;
+            if u1 == C::ZERO {
+                return Err(ContextErr::DivideByZero);
+            }
+
+            let (remainder, quotient, overflowed) = C::unsigned_div_mod(ud, u1);
+            let quotient = if !overflowed {
+                quotient
+            } else {
+                match context.settings.overflow {
+                    Overflow::Wrapping => quotient,
+                    Overflow::Checked => return Err(ContextErr::ArithmeticOverflow),
+                    Overflow::Saturating => !C::ZERO,
+                }
+            };
+
+            context.stack.push(remainder)?;
+            context.stack.push(quotient)?;
+            Ok(())
+        });
+
+        builtin_word!(self : "d." => |context| {
+            // https://forth-standard.org/standard/double/Dd
+            let d = context.pop_double()?;
+            context.write_number(d, C::ZERO)?;
+            context.write(" ");
+            Ok(())
+        });
+
+        builtin_word!(self : "." => |context| {
+            // https://forth-standard.org/standard/core/d
+            let n = context.stack.pop()?;
+            context.write_number(n.to_double(), C::ZERO)?;
+            context.write(" ");
+            Ok(())
+        });
+
+        builtin_word!(self : "u." => |context| {
+            // https://forth-standard.org/standard/core/Ud
+            let u = context.stack.pop()?;
+            context.write_number(C::join(u, C::ZERO), C::ZERO)?;
+            context.write(" ");
+            Ok(())
+        });
+
+        builtin_word!(self : ".r" => |context| {
+            // https://forth-standard.org/standard/core/DotR
+            let width = context.stack.pop()?;
+            let n = context.stack.pop()?;
+            context.write_number(n.to_double(), width)
+        });
+
+        builtin_word!(self : "u.r" => |context| {
+            // https://forth-standard.org/standard/core/UDotR
+            let width = context.stack.pop()?;
+            let u = context.stack.pop()?;
+            context.write_number(C::join(u, C::ZERO), width)
+        });
+
+        builtin_word!(self : ".s" => |context| {
+            // https://forth-standard.org/standard/tools/DotS
+            let base = context.base()?;
+            let mut listing = format!("<{}> ", context.stack.len());
+            for n in context.stack.data() {
+                listing.push_str(&n.to_double().to_str_radix(base));
+                listing.push(' ');
+            }
+            context.write(&listing);
+            Ok(())
+        });
+
+        builtin_word!(self : "s\"", immediate => |context| {
+            // https://forth-standard.org/standard/core/Sq
+            let text = context.parse('"');
+
+            let addr = match context.mode {
+                // Interpreted strings only need to last until the next few are parsed, so they reuse a buffer
+                // rather than filling up the data space.
+                Mode::Interpreting => {
+                    if text.len() > STRING_BUFFER_SIZE {
+                        return Err(ContextErr::StringTooLong);
+                    }
+
+                    let addr = context.strings[context.next_string];
+                    context.next_string = (context.next_string + 1) % context.strings.len();
+                    context.data_space.store(addr, text.as_bytes())?;
+                    addr
+                }
+                Mode::Compiling => context.data_space.append(text.as_bytes())?,
+            };
+
+            context.literal(C::from_usize(addr))?;
+            context.literal(C::from_usize(text.len()))
+        });
+
+        builtin_word!(self : "c\"", immediate => |context| {
+            // https://forth-standard.org/standard/core/Cq
+            let text = context.parse('"');
+            let len = u8::try_from(text.len()).map_err(|_| ContextErr::StringTooLong)?;
+
+            let addr = context.data_space.append(&[len])?;
+            context.data_space.append(text.as_bytes())?;
+            context.literal(C::from_usize(addr))
+        });
+
+        builtin_word!(self : ".\"", immediate => |context| {
+            // https://forth-standard.org/standard/core/Dotq
+            let text = context.parse('"');
+
+            match context.mode {
+                Mode::Interpreting => context.write(&text),
+                Mode::Compiling => {
+                    let write: Procedure<C> = Box::new(move |context| {
+                        context.write(&text);
+                        Ok(())
+                    });
+                    context.compile(Instruction::Call(Rc::new(Word::Builtin(write))));
+                }
+            }
+            Ok(())
+        });
+
+        builtin_word!(self : "type" => |context| {
+            // https://forth-standard.org/standard/core/TYPE
+            let len = context.stack.pop()?;
+            let addr = context.stack.pop()?;
+            let bytes = context.data_space.fetch(addr.to_usize(), len.to_usize())?;
+            let text = String::from_utf8_lossy(bytes).into_owned();
+            context.write(&text);
+            Ok(())
+        });
+
+        builtin_word!(self : "count" => |context| {
+            // https://forth-standard.org/standard/core/COUNT
+            let addr = context.stack.pop()?;
+            let len = context.data_space.fetch(addr.to_usize(), 1)?[0];
+            context.stack.push(addr.wrapping_add(C::from_usize(1)))?;
+            context.stack.push(C::from_usize(len as usize))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "emit" => |context| {
+            // https://forth-standard.org/standard/core/EMIT
+            let x = context.stack.pop()?;
+            let c = std::char::from_u32(x.to_usize() as u32).unwrap_or(std::char::REPLACEMENT_CHARACTER);
+            context.write(&c.to_string());
+            Ok(())
+        });
+
+        builtin_word!(self : "cr" => |context| {
+            // https://forth-standard.org/standard/core/CR
+            context.write("\n");
+            Ok(())
+        });
+
+        builtin_word!(self : "bl" => |context| {
+            // https://forth-standard.org/standard/core/BL
+            context.stack.push(C::from_usize(32))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "space" => |context| {
+            // https://forth-standard.org/standard/core/SPACE
+            context.write(" ");
+            Ok(())
+        });
+
+        builtin_word!(self : "spaces" => |context| {
+            // https://forth-standard.org/standard/core/SPACES
+            let n = context.stack.pop()?;
+            context.write_spaces(n.to_isize().max(0) as usize);
+            Ok(())
+        });
+
+        builtin_word!(self : "<#" => |context| {
+            // https://forth-standard.org/standard/core/num-start
+            context.hold.clear();
+            Ok(())
+        });
+
+        builtin_word!(self : "#" => |context| {
+            // https://forth-standard.org/standard/core/num
+            context.hold_digit()?;
+            Ok(())
+        });
+
+        builtin_word!(self : "#s" => |context| {
+            // https://forth-standard.org/standard/core/numS
+            while context.hold_digit()? != C::Double::ZERO {}
+            Ok(())
+        });
+
+        builtin_word!(self : "hold" => |context| {
+            // https://forth-standard.org/standard/core/HOLD
+            let c = context.stack.pop()?;
+            context.hold(c.to_usize() as u8)
+        });
+
+        builtin_word!(self : "sign" => |context| {
+            // https://forth-standard.org/standard/core/SIGN
+            let n = context.stack.pop()?;
+            if n < C::ZERO {
+                context.hold(b'-')?;
+            }
+            Ok(())
+        });
+
+        builtin_word!(self : "#>" => |context| {
+            // https://forth-standard.org/standard/core/num-end
+            context.pop_double()?;
+            let held = std::mem::take(&mut context.hold);
+            context.data_space.store(context.pictured, &held)?;
+            context.stack.push(C::from_usize(context.pictured))?;
+            context.stack.push(C::from_usize(held.len()))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "2!" => |context| {
+            // https://forth-standard.org/standard/core/TwoStore
+            let addr = context.stack.pop()?;
+            let x2 = context.stack.pop()?;
+            let x1 = context.stack.pop()?;
+            context.store_cell(addr, x2)?;
+            context.store_cell(addr.wrapping_add(C::from_usize(C::SIZE)), x1)
+        });
+
+        builtin_word!(self : "2@" => |context| {
+            // https://forth-standard.org/standard/core/TwoFetch
+            let addr = context.stack.pop()?;
+            let x2 = context.fetch_cell(addr)?;
+            let x1 = context.fetch_cell(addr.wrapping_add(C::from_usize(C::SIZE)))?;
+            context.stack.push(x1)?;
+            context.stack.push(x2)?;
+            Ok(())
+        });
+
+        builtin_word!(self : "f+" => |context| {
+            // https://forth-standard.org/standard/float/FPlus
+            context.float_binary_op(|r1, r2| r1 + r2)
+        });
+
+        builtin_word!(self : "f-" => |context| {
+            // https://forth-standard.org/standard/float/FMinus
+            context.float_binary_op(|r1, r2| r1 - r2)
+        });
+
+        builtin_word!(self : "f*" => |context| {
+            // https://forth-standard.org/standard/float/FTimes
+            context.float_binary_op(|r1, r2| r1 * r2)
+        });
+
+        builtin_word!(self : "f/" => |context| {
+            // https://forth-standard.org/standard/float/FDiv
+            context.float_binary_op(|r1, r2| r1 / r2)
+        });
+
+        builtin_word!(self : "f**" => |context| {
+            // https://forth-standard.org/standard/float/FTimesTimes
+            context.float_binary_op(f64::powf)
+        });
+
+        builtin_word!(self : "fmin" => |context| {
+            // https://forth-standard.org/standard/float/FMIN
+            context.float_binary_op(f64::min)
+        });
+
+        builtin_word!(self : "fmax" => |context| {
+            // https://forth-standard.org/standard/float/FMAX
+            context.float_binary_op(f64::max)
+        });
+
+        builtin_word!(self : "fnegate" => |context| {
+            // https://forth-standard.org/standard/float/FNEGATE
+            context.float_unary_op(|r| -r)
+        });
+
+        builtin_word!(self : "fabs" => |context| {
+            // https://forth-standard.org/standard/float/FABS
+            context.float_unary_op(f64::abs)
+        });
+
+        builtin_word!(self : "fsqrt" => |context| {
+            // https://forth-standard.org/standard/float/FSQRT
+            context.float_unary_op(f64::sqrt)
+        });
+
+        builtin_word!(self : "floor" => |context| {
+            // https://forth-standard.org/standard/float/FLOOR
+            context.float_unary_op(f64::floor)
+        });
+
+        builtin_word!(self : "fround" => |context| {
+            // https://forth-standard.org/standard/float/FROUND
+            context.float_unary_op(f64::round_ties_even)
+        });
+
+        builtin_word!(self : "ftrunc" => |context| {
+            // https://forth-standard.org/standard/float/FTRUNC
+            context.float_unary_op(f64::trunc)
+        });
+
+        builtin_word!(self : "fsin" => |context| {
+            // https://forth-standard.org/standard/float/FSIN
+            context.float_unary_op(f64::sin)
+        });
+
+        builtin_word!(self : "fcos" => |context| {
+            // https://forth-standard.org/standard/float/FCOS
+            context.float_unary_op(f64::cos)
+        });
+
+        builtin_word!(self : "ftan" => |context| {
+            // https://forth-standard.org/standard/float/FTAN
+            context.float_unary_op(f64::tan)
+        });
+
+        builtin_word!(self : "fexp" => |context| {
+            // https://forth-standard.org/standard/float/FEXP
+            context.float_unary_op(f64::exp)
+        });
+
+        builtin_word!(self : "fln" => |context| {
+            // https://forth-standard.org/standard/float/FLN
+            context.float_unary_op(f64::ln)
+        });
+
+        builtin_word!(self : "flog" => |context| {
+            // https://forth-standard.org/standard/float/FLOG
+            context.float_unary_op(f64::log10)
+        });
+
+        builtin_word!(self : "f0=" => |context| {
+            // https://forth-standard.org/standard/float/FZeroEqual
+            let r = context.pop_float()?;
+            context.stack.push(flag(r == 0.0))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "f0<" => |context| {
+            // https://forth-standard.org/standard/float/FZeroless
+            let r = context.pop_float()?;
+            context.stack.push(flag(r < 0.0))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "f<" => |context| {
+            // https://forth-standard.org/standard/float/Fless
+            let r2 = context.pop_float()?;
+            let r1 = context.pop_float()?;
+            context.stack.push(flag(r1 < r2))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "fdrop" => |context| {
+            // https://forth-standard.org/standard/float/FDROP
+            context.pop_float()?;
+            Ok(())
+        });
+
+        builtin_word!(self : "fdup" => |context| {
+            // https://forth-standard.org/standard/float/FDUP
+            let r = context.pop_float()?;
+            context.push_float(r)?;
+            context.push_float(r)
+        });
+
+        builtin_word!(self : "fswap" => |context| {
+            // https://forth-standard.org/standard/float/FSWAP
+            let r2 = context.pop_float()?;
+            let r1 = context.pop_float()?;
+            context.push_float(r2)?;
+            context.push_float(r1)
+        });
+
+        builtin_word!(self : "fover" => |context| {
+            // https://forth-standard.org/standard/float/FOVER
+            let r = *context.float_stack.peek(1).map_err(ContextErr::FloatStackErr)?;
+            context.push_float(r)
+        });
+
+        builtin_word!(self : "frot" => |context| {
+            // https://forth-standard.org/standard/float/FROT
+            let r = context.float_stack.remove(2).map_err(ContextErr::FloatStackErr)?;
+            context.push_float(r)
+        });
+
+        builtin_word!(self : "fdepth" => |context| {
+            // https://forth-standard.org/standard/float/FDEPTH
+            let depth = context.float_stack.len();
+            context.stack.push(C::from_usize(depth))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "s>f" => |context| {
+            // https://forth-standard.org/standard/float/StoF
+            let n = context.stack.pop()?;
+            context.push_float(n.to_f64())
+        });
+
+        builtin_word!(self : "f>s" => |context| {
+            // https://forth-standard.org/standard/float/FtoS
+            let r = context.pop_float()?;
+            context.stack.push(C::from_f64(r))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "d>f" => |context| {
+            // https://forth-standard.org/standard/float/DtoF
+            let d = context.pop_double()?;
+            context.push_float(d.to_f64())
+        });
+
+        builtin_word!(self : "f>d" => |context| {
+            // https://forth-standard.org/standard/float/FtoD
+            let r = context.pop_float()?;
+            context.push_double(C::Double::from_f64(r))
+        });
+
+        builtin_word!(self : "f@" => |context| {
+            // https://forth-standard.org/standard/float/FFetch
+            let addr = context.stack.pop()?;
+            let r = context.fetch_float(addr)?;
+            context.push_float(r)
+        });
+
+        builtin_word!(self : "f!" => |context| {
+            // https://forth-standard.org/standard/float/FStore
+            let addr = context.stack.pop()?;
+            let r = context.pop_float()?;
+            context.store_float(addr, r)
+        });
+
+        builtin_word!(self : "f," => |context| {
+            // https://forth-standard.org/standard/float/FComma
+            let r = context.pop_float()?;
+            context.data_space.append(&r.to_le_bytes())?;
+            Ok(())
+        });
+
+        builtin_word!(self : "fvariable" => |context| {
+            // https://forth-standard.org/standard/float/FVARIABLE
+            context.define_space(FLOAT_SIZE, FLOAT_SIZE)
+        });
+
+        builtin_word!(self : "fconstant" => |context| {
+            // https://forth-standard.org/standard/float/FCONSTANT
+            let name = context.expect_name()?;
+            let r = context.pop_float()?;
+            context.define(&name, Word::Float(r))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "fliteral", immediate => |context| {
+            // https://forth-standard.org/standard/float/FLITERAL
+            context.ensure_compiling("fliteral")?;
+            let r = context.pop_float()?;
+            context.compile(Instruction::FloatLiteral(r));
+            Ok(())
+        });
+
+        builtin_word!(self : "floats" => |context| {
+            // https://forth-standard.org/standard/float/FLOATS
+            let n = context.stack.pop()?;
+            context.stack.push(n.wrapping_mul(C::from_usize(FLOAT_SIZE)))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "float+" => |context| {
+            // https://forth-standard.org/standard/float/FLOATPlus
+            let addr = context.stack.pop()?;
+            context.stack.push(addr.wrapping_add(C::from_usize(FLOAT_SIZE)))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "falign" => |context| {
+            // https://forth-standard.org/standard/float/FALIGN
+            context.align_to(FLOAT_SIZE)
+        });
+
+        builtin_word!(self : "faligned" => |context| {
+            // https://forth-standard.org/standard/float/FALIGNED
+            let addr = context.stack.pop()?;
+            context.stack.push(C::from_usize(aligned(addr.to_usize(), FLOAT_SIZE)))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "f." => |context| {
+            // https://forth-standard.org/standard/float/Fd
+            let r = context.pop_float()?;
+            context.write(&format!("{} ", r));
+            Ok(())
+        });
+
+        builtin_word!(self : "dup" => |context |{
+            let n = context.stack.pop()?;
+            context.stack.push(n)?;
+            context.stack.push(n)?;
+            Ok(())
+        });
+
+        builtin_word!(self : "true" => |context| {
+            // https://forth-standard.org/standard/core/TRUE
+            context.stack.push(flag(true))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "false" => |context| {
+            // https://forth-standard.org/standard/core/FALSE
+            context.stack.push(flag(false))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "=" => |context| {
+            // https://forth-standard.org/standard/core/Equal
+            context.binary_op(|n1, n2| flag(n1 == n2))
+        });
+
+        builtin_word!(self : "<>" => |context| {
+            // https://forth-standard.org/standard/core/ne
+            context.binary_op(|n1, n2| flag(n1 != n2))
+        });
+
+        builtin_word!(self : "<" => |context| {
+            // https://forth-standard.org/standard/core/less
+            context.binary_op(|n1, n2| flag(n1 < n2))
+        });
+
+        builtin_word!(self : ">" => |context| {
+            // https://forth-standard.org/standard/core/more
+            context.binary_op(|n1, n2| flag(n1 > n2))
+        });
+
+        builtin_word!(self : "u<" => |context| {
+            // https://forth-standard.org/standard/core/Uless
+            context.binary_op(|u1, u2| flag(u1.unsigned_lt(u2)))
+        });
+
+        builtin_word!(self : "u>" => |context| {
+            // https://forth-standard.org/standard/core/Umore
+            context.binary_op(|u1, u2| flag(u2.unsigned_lt(u1)))
+        });
+
+        builtin_word!(self : "0=" => |context| {
+            // https://forth-standard.org/standard/core/ZeroEqual
+            context.unary_op(|n| flag(n == C::ZERO))
+        });
+
+        builtin_word!(self : "0<>" => |context| {
+            // https://forth-standard.org/standard/core/Zerone
+            context.unary_op(|n| flag(n != C::ZERO))
+        });
+
+        builtin_word!(self : "0<" => |context| {
+            // https://forth-standard.org/standard/core/Zeroless
+            context.unary_op(|n| flag(n < C::ZERO))
+        });
+
+        builtin_word!(self : "0>" => |context| {
+            // https://forth-standard.org/standard/core/Zeromore
+            context.unary_op(|n| flag(n > C::ZERO))
+        });
+
+        builtin_word!(self : "and" => |context| {
+            // https://forth-standard.org/standard/core/AND
+            context.binary_op(|x1, x2| x1 & x2)
+        });
+
+        builtin_word!(self : "or" => |context| {
+            // https://forth-standard.org/standard/core/OR
+            context.binary_op(|x1, x2| x1 | x2)
+        });
+
+        builtin_word!(self : "xor" => |context| {
+            // https://forth-standard.org/standard/core/XOR
+            context.binary_op(|x1, x2| x1 ^ x2)
+        });
+
+        builtin_word!(self : "invert" => |context| {
+            // https://forth-standard.org/standard/core/INVERT
+            context.unary_op(|x| !x)
+        });
+
+        builtin_word!(self : "lshift" => |context| {
+            // https://forth-standard.org/standard/core/LSHIFT
+            context.binary_op(C::logical_shl)
+        });
+
+        builtin_word!(self : "rshift" => |context| {
+            // https://forth-standard.org/standard/core/RSHIFT
+            context.binary_op(C::logical_shr)
+        });
+
+        builtin_word!(self : "?dup" => |context| {
+            // https://forth-standard.org/standard/core/qDUP
+            let n = *context.stack.peek(0)?;
+            if n != C::ZERO {
+                context.stack.push(n)?;
+            }
+            Ok(())
+        });
+
+        builtin_word!(self : "swap" => |context| {
+            // https://forth-standard.org/standard/core/SWAP
+            let x = context.stack.remove(1)?;
+            context.stack.push(x)?;
+            Ok(())
+        });
+
+        builtin_word!(self : "over" => |context| {
+            // https://forth-standard.org/standard/core/OVER
+            let x = *context.stack.peek(1)?;
+            context.stack.push(x)?;
+            Ok(())
+        });
+
+        builtin_word!(self : "rot" => |context| {
+            // https://forth-standard.org/standard/core/ROT
+            let x = context.stack.remove(2)?;
+            context.stack.push(x)?;
+            Ok(())
+        });
+
+        builtin_word!(self : "-rot" => |context| {
+            let x3 = context.stack.pop()?;
+            let x2 = context.stack.pop()?;
+            let x1 = context.stack.pop()?;
+            context.stack.push(x3)?;
+            context.stack.push(x1)?;
+            context.stack.push(x2)?;
+            Ok(())
+        });
+
+        builtin_word!(self : "nip" => |context| {
+            // https://forth-standard.org/standard/core/NIP
+            context.stack.remove(1)?;
+            Ok(())
+        });
+
+        builtin_word!(self : "tuck" => |context| {
+            // https://forth-standard.org/standard/core/TUCK
+            let x2 = context.stack.pop()?;
+            let x1 = context.stack.pop()?;
+            context.stack.push(x2)?;
+            context.stack.push(x1)?;
+            context.stack.push(x2)?;
+            Ok(())
+        });
+
+        builtin_word!(self : "pick" => |context| {
+            // https://forth-standard.org/standard/core/PICK
+            let depth = context.stack.pop()?;
+            let x = *context.stack.peek(depth.to_usize())?;
+            context.stack.push(x)?;
+            Ok(())
+        });
+
+        builtin_word!(self : "roll" => |context| {
+            // https://forth-standard.org/standard/core/ROLL
+            let depth = context.stack.pop()?;
+            let x = context.stack.remove(depth.to_usize())?;
+            context.stack.push(x)?;
+            Ok(())
+        });
+
+        builtin_word!(self : "depth" => |context| {
+            // https://forth-standard.org/standard/core/DEPTH
+            let depth = context.stack.len();
+            context.stack.push(C::from_usize(depth))?;
+            Ok(())
+        });
+
+        builtin_word!(self : "2drop" => |context| {
+            // https://forth-standard.org/standard/core/TwoDROP
+            context.stack.pop()?;
+            context.stack.pop()?;
+            Ok(())
+        });
+
+        builtin_word!(self : "2dup" => |context| {
+            // https://forth-standard.org/standard/core/TwoDUP
+            let x1 = *context.stack.peek(1)?;
+            let x2 = *context.stack.peek(0)?;
+            context.stack.push(x1)?;
+            context.stack.push(x2)?;
+            Ok(())
+        });
+
+        builtin_word!(self : "2swap" => |context| {
+            // https://forth-standard.org/standard/core/TwoSWAP
+            let x1 = context.stack.remove(3)?;
+            let x2 = context.stack.remove(2)?;
+            context.stack.push(x1)?;
+            context.stack.push(x2)?;
+            Ok(())
+        });
+
+        builtin_word!(self : "2over" => |context| {
+            // https://forth-standard.org/standard/core/TwoOVER
+            let x1 = *context.stack.peek(3)?;
+            let x2 = *context.stack.peek(2)?;
+            context.stack.push(x1)?;
+            context.stack.push(x2)?;
+            Ok(())
+        });
+
+        Ok(())
+    }
+}
+
+/// Converts a boolean to a well-formed flag, with all bits set for true.
+fn flag<C: Cell>(b: bool) -> C {
+    if b {
+        !C::ZERO
+    } else {
+        C::ZERO
+    }
+}
+
+/// Rounds the address up to the next multiple of the alignment, wrapping around past the largest address.
+fn aligned(addr: data_space::Addr, alignment: usize) -> data_space::Addr {
+    addr.div_ceil(alignment).wrapping_mul(alignment)
+}
+
+/// Returns the largest address a cell can hold when read as unsigned.
+fn max_address<C: Cell>() -> usize {
+    match 1usize.checked_shl(8 * C::SIZE as u32) {
+        Some(addresses) => addresses - 1,
+        None => usize::MAX,
+    }
+}
+
+/// Returns the number of characters pictured numeric output has room for, enough for a double in binary.
+fn pictured_size<C: Cell>() -> usize {
+    2 * 8 * C::SIZE + 2
+}
+
+/// Returns the number of bytes reserved at the start of the data space for `base` and the pictured numeric output
+/// and string buffers.
+fn reserved_size<C: Cell>() -> usize {
+    let bytes = C::SIZE + pictured_size::<C>() + STRING_BUFFERS * STRING_BUFFER_SIZE;
+    aligned(bytes, C::SIZE)
+}
+
+/// Returns the upper case character for a digit.
+fn digit_char(digit: u32) -> u8 {
+    std::char::from_digit(digit, 36)
+        .unwrap_or('?')
+        .to_ascii_uppercase() as u8
+}
+
+/// Parses a character literal such as `'A'`, returning the character.
+fn parse_char(word: &str) -> Option<char> {
+    let mut chars = word.strip_prefix('\'')?.strip_suffix('\'')?.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+/// Parses a float written the standard way, which requires an exponent: `1.5e0`, `-2E3` or `1.e`.
+fn parse_float(word: &str) -> Option<f64> {
+    let e = word.find(['e', 'E'])?;
+    let (significand, exponent) = (&word[..e], &word[e + 1..]);
+
+    let is_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    let digits = significand.strip_prefix(['-', '+']).unwrap_or(significand);
+    let exponent_digits = exponent.strip_prefix(['-', '+']).unwrap_or(exponent);
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+    if whole.is_empty() || !is_digits(whole) || !is_digits(fraction) || !is_digits(exponent_digits)
+    {
+        return None;
+    }
+
+    let exponent = if exponent_digits.is_empty() {
+        "0"
+    } else {
+        exponent
+    };
+    format!("{}e{}", significand, exponent).parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_div_divides() {
+        let mut f = Context::new(333, 343);
+        f.eval("7 4 /".into()).unwrap();
+        assert_eq!(1, f.stack()[0]);
+
+        f.reset();
+
+        f.eval("-9 3 /".into()).unwrap();
+        assert_eq!(-3, f.stack()[0]);
+
+        f.reset();
+
+        assert_eq!(
+            ContextErr::DivideByZero,
+            f.eval("-9 0 /".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn mod_takes_sign_of_dividend_by_default() {
+        let mut f = Context::new(333, 343);
+        f.eval("7 2 mod -7 2 mod 7 -2 mod".into()).unwrap();
+        assert_eq!(&[1, -1, 1], f.stack());
+    }
+
+    #[test]
+    fn slash_mod_rounds_towards_zero_by_default() {
+        let mut f = Context::new(333, 343);
+        f.eval("7 2 /mod -7 2 /mod".into()).unwrap();
+        assert_eq!(&[1, 3, -1, -3], f.stack());
+    }
+
+    #[test]
+    fn star_slash_keeps_double_intermediate() {
+        let mut f = Context::new(333, 343);
+        f.eval("100000 100000 1000000 */".into()).unwrap();
+        assert_eq!(&[10000], f.stack());
+    }
+
+    #[test]
+    fn star_slash_rounds_towards_zero_by_default() {
+        let mut f = Context::new(333, 343);
+        f.eval("-7 1 2 */".into()).unwrap();
+        assert_eq!(&[-3], f.stack());
+    }
+
+    #[test]
+    fn star_slash_mod_pushes_remainder_and_quotient() {
+        let mut f = Context::new(333, 343);
+        f.eval("7 3 2 */mod".into()).unwrap();
+        assert_eq!(&[1, 10], f.stack());
+    }
+
+    #[test]
+    fn floored_mod_takes_sign_of_divisor() {
+        let settings = Settings {
+            division: Division::Floored,
+            ..Settings::default()
+        };
+        let mut f = Context::<Datum>::with_settings(333, 343, 1024, settings);
+        f.eval("7 2 mod -7 2 mod 7 -2 mod".into()).unwrap();
+        assert_eq!(&[1, 1, -1], f.stack());
+    }
+
+    #[test]
+    fn floored_slash_mod_rounds_towards_negative_infinity() {
+        let settings = Settings {
+            division: Division::Floored,
+            ..Settings::default()
+        };
+        let mut f = Context::<Datum>::with_settings(333, 343, 1024, settings);
+        f.eval("7 2 /mod -7 2 /mod".into()).unwrap();
+        assert_eq!(&[1, 3, 1, -4], f.stack());
+    }
+
+    #[test]
+    fn floored_star_slash_rounds_towards_negative_infinity() {
+        let settings = Settings {
+            division: Division::Floored,
+            ..Settings::default()
+        };
+        let mut f = Context::<Datum>::with_settings(333, 343, 1024, settings);
+        f.eval("-7 1 2 */".into()).unwrap();
+        assert_eq!(&[-4], f.stack());
+    }
+
+    #[test]
+    fn floored_slash_rounds_towards_negative_infinity() {
+        let settings = Settings {
+            division: Division::Floored,
+            ..Settings::default()
+        };
+        let mut f = Context::<Datum>::with_settings(333, 343, 1024, settings);
+        f.eval("-7 2 /".into()).unwrap();
+        assert_eq!(&[-4], f.stack());
+    }
+
+    #[test]
+    fn mod_by_zero_returns_err() {
+        let mut f = Context::new(333, 343);
+        assert_eq!(
+            ContextErr::DivideByZero,
+            f.eval("1 0 mod".into()).unwrap_err()
+        );
+
+        f.reset();
+        assert_eq!(
+            ContextErr::DivideByZero,
+            f.eval("1 0 /mod".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn star_slash_by_zero_returns_err() {
+        let mut f = Context::new(333, 343);
+        assert_eq!(
+            ContextErr::DivideByZero,
+            f.eval("1 2 0 */".into()).unwrap_err()
+        );
+
+        f.reset();
+        assert_eq!(
+            ContextErr::DivideByZero,
+            f.eval("1 2 0 */mod".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn negate_changes_sign() {
+        let mut f = Context::new(333, 343);
+        f.eval("5 negate -5 negate".into()).unwrap();
+        assert_eq!(&[-5, 5], f.stack());
+    }
+
+    #[test]
+    fn abs_removes_sign() {
+        let mut f = Context::new(333, 343);
+        f.eval("-5 abs 5 abs".into()).unwrap();
+        assert_eq!(&[5, 5], f.stack());
+    }
+
+    #[test]
+    fn min_and_max_pick_smaller_and_larger() {
+        let mut f = Context::new(333, 343);
+        f.eval("3 -4 min 3 -4 max".into()).unwrap();
+        assert_eq!(&[-4, 3], f.stack());
+    }
+
+    #[test]
+    fn one_plus_and_one_minus_step_by_one() {
+        let mut f = Context::new(333, 343);
+        f.eval("5 1+ 5 1-".into()).unwrap();
+        assert_eq!(&[6, 4], f.stack());
+    }
+
+    #[test]
+    fn wrapping_overflow_wraps_sums_and_products() {
+        let mut f = Context::new(333, 343);
+        f.eval("2147483647 1 + -2147483648 1 - 65536 65536 *".into())
+            .unwrap();
+        assert_eq!(&[Datum::MIN, Datum::MAX, 0], f.stack());
+    }
+
+    #[test]
+    fn wrapping_overflow_wraps_quotients() {
+        let mut f = Context::new(333, 343);
+        f.eval("-2147483648 -1 / -2147483648 -1 /mod nip".into())
+            .unwrap();
+        assert_eq!(&[Datum::MIN, Datum::MIN], f.stack());
+    }
+
+    #[test]
+    fn wrapping_overflow_wraps_scaled_results() {
+        let mut f = Context::new(333, 343);
+        f.eval("2147483647 2 1 */ 2147483647 2 1 */mod nip".into())
+            .unwrap();
+        assert_eq!(&[-2, -2], f.stack());
+    }
+
+    #[test]
+    fn wrapping_overflow_wraps_single_cell_words() {
+        let mut f = Context::new(333, 343);
+        f.eval("-2147483648 negate -2147483648 abs 2147483647 1+ -2147483648 1-".into())
+            .unwrap();
+        assert_eq!(&[Datum::MIN, Datum::MIN, Datum::MIN, Datum::MAX], f.stack());
+    }
+
+    #[test]
+    fn saturating_overflow_clamps_sums_and_products() {
+        let settings = Settings {
+            overflow: Overflow::Saturating,
+            ..Settings::default()
+        };
+        let mut f = Context::<Datum>::with_settings(333, 343, 1024, settings);
+        f.eval("2147483647 1 + -2147483648 1 - 65536 65536 *".into())
+            .unwrap();
+        assert_eq!(&[Datum::MAX, Datum::MIN, Datum::MAX], f.stack());
+    }
+
+    #[test]
+    fn saturating_overflow_clamps_quotients() {
+        let settings = Settings {
+            overflow: Overflow::Saturating,
+            ..Settings::default()
+        };
+        let mut f = Context::<Datum>::with_settings(333, 343, 1024, settings);
+        f.eval("-2147483648 -1 / -2147483648 -1 /mod nip".into())
+            .unwrap();
+        assert_eq!(&[Datum::MAX, Datum::MAX], f.stack());
+    }
+
+    #[test]
+    fn saturating_overflow_clamps_scaled_results() {
+        let settings = Settings {
+            overflow: Overflow::Saturating,
+            ..Settings::default()
+        };
+        let mut f = Context::<Datum>::with_settings(333, 343, 1024, settings);
+        f.eval("2147483647 2 1 */ 2147483647 2 1 */mod nip".into())
+            .unwrap();
+        assert_eq!(&[Datum::MAX, Datum::MAX], f.stack());
+    }
+
+    #[test]
+    fn saturating_overflow_clamps_single_cell_words() {
+        let settings = Settings {
+            overflow: Overflow::Saturating,
+            ..Settings::default()
+        };
+        let mut f = Context::<Datum>::with_settings(333, 343, 1024, settings);
+        f.eval("-2147483648 negate -2147483648 abs 2147483647 1+ -2147483648 1-".into())
+            .unwrap();
+        assert_eq!(&[Datum::MAX, Datum::MAX, Datum::MAX, Datum::MIN], f.stack());
+    }
+
+    #[test]
+    fn checked_overflow_rejects_sums_and_products() {
+        let settings = Settings {
+            overflow: Overflow::Checked,
+            ..Settings::default()
+        };
+        let mut f = Context::<Datum>::with_settings(333, 343, 1024, settings);
+        assert_eq!(
+            ContextErr::ArithmeticOverflow,
+            f.eval("2147483647 1 +".into()).unwrap_err()
+        );
+
+        f.reset();
+        assert_eq!(
+            ContextErr::ArithmeticOverflow,
+            f.eval("-2147483648 1 -".into()).unwrap_err()
+        );
+
+        f.reset();
+        assert_eq!(
+            ContextErr::ArithmeticOverflow,
+            f.eval("65536 65536 *".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn checked_overflow_rejects_quotients() {
+        let settings = Settings {
+            overflow: Overflow::Checked,
+            ..Settings::default()
+        };
+        let mut f = Context::<Datum>::with_settings(333, 343, 1024, settings);
+        assert_eq!(
+            ContextErr::ArithmeticOverflow,
+            f.eval("-2147483648 -1 /".into()).unwrap_err()
+        );
+
+        f.reset();
+        assert_eq!(
+            ContextErr::ArithmeticOverflow,
+            f.eval("-2147483648 -1 /mod nip".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn checked_overflow_rejects_scaled_results() {
+        let settings = Settings {
+            overflow: Overflow::Checked,
+            ..Settings::default()
+        };
+        let mut f = Context::<Datum>::with_settings(333, 343, 1024, settings);
+        assert_eq!(
+            ContextErr::ArithmeticOverflow,
+            f.eval("2147483647 2 1 */".into()).unwrap_err()
+        );
+
+        f.reset();
+        assert_eq!(
+            ContextErr::ArithmeticOverflow,
+            f.eval("2147483647 2 1 */mod nip".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn checked_overflow_rejects_single_cell_words() {
+        let settings = Settings {
+            overflow: Overflow::Checked,
+            ..Settings::default()
+        };
+        let mut f = Context::<Datum>::with_settings(333, 343, 1024, settings);
+        assert_eq!(
+            ContextErr::ArithmeticOverflow,
+            f.eval("-2147483648 negate".into()).unwrap_err()
+        );
+
+        f.reset();
+        assert_eq!(
+            ContextErr::ArithmeticOverflow,
+            f.eval("-2147483648 abs".into()).unwrap_err()
+        );
+
+        f.reset();
+        assert_eq!(
+            ContextErr::ArithmeticOverflow,
+            f.eval("2147483647 1+".into()).unwrap_err()
+        );
+
+        f.reset();
+        assert_eq!(
+            ContextErr::ArithmeticOverflow,
+            f.eval("-2147483648 1-".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn checked_overflow_allows_results_in_range() {
+        let settings = Settings {
+            overflow: Overflow::Checked,
+            ..Settings::default()
+        };
+        let mut f = Context::with_settings(333, 343, 1024, settings);
+        f.eval("2147483646 1 + -2147483647 1 - 46340 46340 *".into())
+            .unwrap();
+        assert_eq!(&[Datum::MAX, Datum::MIN, 2147395600], f.stack());
+    }
+
+    #[test]
+    fn trailing_dot_parses_double() {
+        let mut f = Context::new(333, 343);
+        f.eval("1. -1. 5000000000.".into()).unwrap();
+        assert_eq!(&[1, 0, -1, -1, 705032704, 1], f.stack());
+
+        f.reset();
+        f.eval(": big 5000000000. ; big".into()).unwrap();
+        assert_eq!(&[705032704, 1], f.stack());
+    }
+
+    #[test]
+    fn d_plus_adds_doubles() {
+        let mut f = Context::new(333, 343);
+        f.eval("1. 2. d+ 4294967295. 1. d+".into()).unwrap();
+        assert_eq!(&[3, 0, 0, 1], f.stack());
+    }
+
+    #[test]
+    fn d_minus_borrows_from_high_cell() {
+        let mut f = Context::new(333, 343);
+        f.eval("4294967296. 1. d-".into()).unwrap();
+        assert_eq!(&[-1, 0], f.stack());
+    }
+
+    #[test]
+    fn s_to_d_sign_extends() {
+        let mut f = Context::new(333, 343);
+        f.eval("-5 s>d 5 s>d".into()).unwrap();
+        assert_eq!(&[-5, -1, 5, 0], f.stack());
+    }
+
+    #[test]
+    fn m_star_pushes_signed_double_product() {
+        let mut f = Context::new(333, 343);
+        f.eval("65536 65536 m* -2 3 m*".into()).unwrap();
+        assert_eq!(&[0, 1, -6, -1], f.stack());
+    }
+
+    #[test]
+    fn um_star_pushes_unsigned_double_product() {
+        let mut f = Context::new(333, 343);
+        f.eval("-1 -1 um*".into()).unwrap();
+        assert_eq!(&[1, -2], f.stack());
+    }
+
+    #[test]
+    fn um_slash_mod_divides_unsigned_double() {
+        let mut f = Context::new(333, 343);
+        f.eval("0 1 2 um/mod 7 0 2 um/mod".into()).unwrap();
+        assert_eq!(&[0, Datum::MIN, 1, 3], f.stack());
+    }
+
+    #[test]
+    fn d_dot_consumes_double() {
+        let mut f = Context::new(333, 343);
+        f.eval("1. d.".into()).unwrap();
+        assert_eq!("1 ", f.take_output());
+        assert!(f.stack().is_empty());
+    }
+
+    #[test]
+    fn hex_and_decimal_change_base() {
+        let mut f = Context::new(333, 343);
+        f.eval("hex ff 10 decimal 10".into()).unwrap();
+        assert_eq!(&[255, 16, 10], f.stack());
+    }
+
+    #[test]
+    fn binary_parses_base_two() {
+        let mut f = Context::new(333, 343);
+        f.eval("binary 101 -11 decimal".into()).unwrap();
+        assert_eq!(&[5, -3], f.stack());
+    }
+
+    #[test]
+    fn base_may_be_stored_directly() {
+        let mut f = Context::new(333, 343);
+        f.eval("36 base ! zz decimal".into()).unwrap();
+        assert_eq!(&[1295], f.stack());
+    }
+
+    #[test]
+    fn base_holds_current_radix() {
+        let mut f = Context::new(333, 343);
+        f.eval("hex base @ decimal base @".into()).unwrap();
+        assert_eq!(&[16, 10], f.stack());
+    }
+
+    #[test]
+    fn digits_above_nine_ignore_case() {
+        let mut f = Context::new(333, 343);
+        f.eval("hex 1E decimal".into()).unwrap();
+        assert_eq!(&[30], f.stack());
+    }
+
+    #[test]
+    fn doubles_parse_in_current_base() {
+        let mut f = Context::new(333, 343);
+        f.eval("hex ffffffff. decimal".into()).unwrap();
+        assert_eq!(&[-1, 0], f.stack());
+    }
+
+    #[test]
+    fn prefixes_override_base() {
+        let mut f = Context::new(333, 343);
+        f.eval("$FF #10 %1010 $-10 hex #10 $10 %10 decimal".into())
+            .unwrap();
+        assert_eq!(&[255, 10, 10, -16, 10, 16, 2], f.stack());
+    }
+
+    #[test]
+    fn quoted_character_parses_as_code() {
+        let mut f = Context::new(333, 343);
+        f.eval("'A' 'z'".into()).unwrap();
+        assert_eq!(&[65, 122], f.stack());
+    }
+
+    #[test]
+    fn compiled_literals_keep_their_base() {
+        let mut f = Context::new(333, 343);
+        f.eval(": h $ff ; hex h decimal".into()).unwrap();
+        assert_eq!(&[255], f.stack());
+    }
+
+    #[test]
+    fn invalid_base_returns_err() {
+        let mut f = Context::new(333, 343);
+        assert_eq!(
+            ContextErr::InvalidBase,
+            f.eval("1 base ! 10".into()).unwrap_err()
+        );
+        assert_eq!(
+            ContextErr::InvalidBase,
+            f.eval("decimal 1. 37 base ! d.".into()).unwrap_err()
+        );
+
+        f.eval("decimal 10".into()).unwrap();
+        assert_eq!(&[10], f.stack());
+        assert!(matches!(
+            f.eval("$".into()).unwrap_err(),
+            ContextErr::Parse(_)
+        ));
+        assert!(matches!(
+            f.eval("'ab'".into()).unwrap_err(),
+            ContextErr::Parse(_)
+        ));
+    }
+
+    #[test]
+    fn dot_writes_number_and_space() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 -2 .".into()).unwrap();
+        assert_eq!("-2 ", f.take_output());
+        assert_eq!(&[1], f.stack());
+        assert_eq!("", f.output());
+    }
+
+    #[test]
+    fn dot_writes_in_current_base() {
+        let mut f = Context::new(333, 343);
+        f.eval("255 hex . decimal 255 .".into()).unwrap();
+        assert_eq!("FF 255 ", f.take_output());
+        assert!(f.stack().is_empty());
+    }
+
+    #[test]
+    fn u_dot_writes_unsigned() {
+        let mut f = Context::new(333, 343);
+        f.eval("-1 u. 10 u.".into()).unwrap();
+        assert_eq!("4294967295 10 ", f.take_output());
+        assert!(f.stack().is_empty());
+    }
+
+    #[test]
+    fn dot_r_right_aligns_in_field() {
+        let mut f = Context::new(333, 343);
+        f.eval("42 5 .r -3 4 .r 7 0 .r".into()).unwrap();
+        assert_eq!("   42  -37", f.take_output());
+        assert!(f.stack().is_empty());
+    }
+
+    #[test]
+    fn u_dot_r_right_aligns_in_current_base() {
+        let mut f = Context::new(333, 343);
+        f.eval("5 binary 110 u.r".into()).unwrap();
+        assert_eq!("   101", f.take_output());
+        assert!(f.stack().is_empty());
+    }
+
+    #[test]
+    fn dot_s_lists_stack_without_changing_it() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 2 -3 .s".into()).unwrap();
+        assert_eq!("<3> 1 2 -3 ", f.take_output());
+        assert_eq!(&[1, 2, -3], f.stack());
+    }
+
+    #[test]
+    fn d_dot_writes_double_in_current_base() {
+        let mut f = Context::new(333, 343);
+        f.eval("-5. d. 255. hex d.".into()).unwrap();
+        assert_eq!("-5 FF ", f.take_output());
+        assert!(f.stack().is_empty());
+    }
+
+    #[test]
+    fn f_dot_writes_float() {
+        let mut f = Context::new(333, 343);
+        f.eval("2.5e f.".into()).unwrap();
+        assert_eq!("2.5 ", f.take_output());
+        assert!(f.stack().is_empty());
+    }
+
+    #[test]
+    fn reset_discards_buffered_output() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 . 2 .".into()).unwrap();
+        f.reset();
+        assert_eq!("", f.take_output());
+    }
+
+    #[test]
+    fn output_field_width_is_capped() {
+        let mut f = Context::new(333, 343);
+        f.eval("5 70000 .r".into()).unwrap();
+        let output = f.take_output();
+        assert_eq!(MAX_SPACES + 1, output.len());
+        assert!(output.ends_with(" 5"));
+
+        f.eval("5 2147483647 u.r".into()).unwrap();
+        assert_eq!(MAX_SPACES + 1, f.take_output().len());
+    }
+
+    #[test]
+    fn pictured_output_builds_string() {
+        let mut f = Context::new(333, 343);
+        f.eval("-1234 dup abs s>d <# # # 46 hold #s rot sign #>".into())
+            .unwrap();
+        let len = f.pop().unwrap();
+        let addr = f.pop().unwrap();
+        assert_eq!(6, len);
+
+        let mut text = String::new();
+        for i in 0..len {
+            f.eval(format!("{} c@", addr + i)).unwrap();
+            text.push(f.pop().unwrap() as u8 as char);
+        }
+        assert_eq!("-12.34", text);
+
+        f.eval("255. hex <# #s #> nip decimal 0. <# #s #> nip".into())
+            .unwrap();
+        assert_eq!(&[2, 1], f.stack());
+    }
+
+    #[test]
+    fn pictured_output_overflow_returns_err() {
+        let mut f = Context::new(333, 343);
+        assert_eq!(
+            ContextErr::PicturedOutputOverflow,
+            f.eval(": fill <# 100 0 do 42 hold loop ; fill".into())
+                .unwrap_err()
+        );
+    }
+
+    #[test]
+    fn s_quote_pushes_address_and_length() {
+        let mut f = Context::new(333, 343);
+        f.eval("s\" hello world\" type".into()).unwrap();
+        assert_eq!("hello world", f.take_output());
+        assert!(f.stack().is_empty());
+    }
+
+    #[test]
+    fn s_quote_skips_one_leading_space() {
+        let mut f = Context::new(333, 343);
+        f.eval("s\" hi\" nip s\" \" nip".into()).unwrap();
+        assert_eq!(&[2, 0], f.stack());
+    }
+
+    #[test]
+    fn dot_quote_writes_text() {
+        let mut f = Context::new(333, 343);
+        f.eval(".\"  two  spaces\" 1".into()).unwrap();
+        assert_eq!(" two  spaces", f.take_output());
+        assert_eq!(&[1], f.stack());
+    }
+
+    #[test]
+    fn dot_quote_compiles_text() {
+        let mut f = Context::new(333, 343);
+        f.eval(": greet .\" hello, \" type ; s\" bob\" greet".into())
+            .unwrap();
+        assert_eq!("hello, bob", f.take_output());
+        assert!(f.stack().is_empty());
+    }
+
+    #[test]
+    fn compiled_s_quote_keeps_string() {
+        let mut f = Context::new(333, 343);
+        f.eval(": msg s\" compiled\" ; msg type msg nip".into())
+            .unwrap();
+        assert_eq!("compiled", f.take_output());
+        assert_eq!(&[8], f.stack());
+    }
+
+    #[test]
+    fn c_quote_pushes_counted_string() {
+        let mut f = Context::new(333, 343);
+        f.eval("c\" counted\" count type".into()).unwrap();
+        assert_eq!("counted", f.take_output());
+        assert!(f.stack().is_empty());
+    }
+
+    #[test]
+    fn compiled_c_quote_keeps_string() {
+        let mut f = Context::new(333, 343);
+        f.eval(": cs c\" abc\" ; cs count nip cs c@".into())
+            .unwrap();
+        assert_eq!(&[3, 3], f.stack());
+    }
+
+    #[test]
+    fn interpreted_strings_do_not_fill_data_space() {
+        let mut f = Context::new(333, 343);
+        f.eval("here".into()).unwrap();
+        for _ in 0..1000 {
+            f.eval("s\" a string that would soon fill the data space\" 2drop".into())
+                .unwrap();
+        }
+        f.eval("here".into()).unwrap();
+        assert_eq!(f.stack()[0], f.stack()[1]);
+    }
+
+    #[test]
+    fn last_two_interpreted_strings_are_kept() {
+        let mut f = Context::new(333, 343);
+        f.eval("s\" first\" s\" second\" 2swap type type".into())
+            .unwrap();
+        assert_eq!("firstsecond", f.take_output());
+
+        let line = format!("s\" {}\"", "x".repeat(256));
+        assert_eq!(ContextErr::StringTooLong, f.eval(line).unwrap_err());
+    }
+
+    #[test]
+    fn unterminated_string_takes_rest_of_line() {
+        let mut f = Context::new(333, 343);
+        f.eval("s\" no end\n".into()).unwrap();
+        f.eval("type".into()).unwrap();
+        assert_eq!("no end", f.take_output());
+    }
+
+    #[test]
+    fn text_output_words_write_characters() {
+        let mut f = Context::new(333, 343);
+        f.eval("72 emit 105 emit cr bl emit space 3 spaces -1 spaces 42 emit".into())
+            .unwrap();
+        assert_eq!("Hi\n     *", f.take_output());
+    }
+
+    #[test]
+    fn spaces_count_is_capped() {
+        let mut f = Context::new(333, 343);
+        f.eval("2147483647 spaces".into()).unwrap();
+        assert_eq!(MAX_SPACES, f.take_output().len());
+    }
+
+    #[test]
+    fn counted_string_too_long_returns_err() {
+        let mut f = Context::new(333, 343);
+        let line = format!("c\" {}\"", "x".repeat(256));
+        assert_eq!(ContextErr::StringTooLong, f.eval(line).unwrap_err());
+    }
+
+    #[test]
+    fn exponent_parses_float() {
+        let mut f = Context::new(333, 343);
+        f.eval("1.5e0 -2E3 1.e 25e-1 +1.25E+2".into()).unwrap();
+        assert_eq!(&[1.5, -2000.0, 1.0, 2.5, 125.0], f.float_stack());
+        assert!(f.stack().is_empty());
+
+        f.reset();
+        f.eval(": half 5e-1 ; half half f+".into()).unwrap();
+        assert_eq!(&[1.0], f.float_stack());
+
+        assert!(matches!(
+            f.eval("1.5".into()).unwrap_err(),
+            ContextErr::Parse(_)
+        ));
+        assert!(matches!(
+            f.eval("e5".into()).unwrap_err(),
+            ContextErr::Parse(_)
+        ));
+    }
+
+    #[test]
+    fn f_plus_adds_floats() {
+        let mut f = Context::new(333, 343);
+        f.eval("1e 2e f+".into()).unwrap();
+        assert_eq!(&[3.0], f.float_stack());
+    }
+
+    #[test]
+    fn f_minus_subtracts_floats() {
+        let mut f = Context::new(333, 343);
+        f.eval("1e 2e f-".into()).unwrap();
+        assert_eq!(&[-1.0], f.float_stack());
+    }
+
+    #[test]
+    fn f_star_multiplies_floats() {
+        let mut f = Context::new(333, 343);
+        f.eval("3e 4e f*".into()).unwrap();
+        assert_eq!(&[12.0], f.float_stack());
+    }
+
+    #[test]
+    fn f_slash_divides_floats() {
+        let mut f = Context::new(333, 343);
+        f.eval("1e 4e f/".into()).unwrap();
+        assert_eq!(&[0.25], f.float_stack());
+    }
+
+    #[test]
+    fn f_star_star_raises_to_power() {
+        let mut f = Context::new(333, 343);
+        f.eval("2e 10e f**".into()).unwrap();
+        assert_eq!(&[1024.0], f.float_stack());
+    }
+
+    #[test]
+    fn fsqrt_fnegate_and_fabs() {
+        let mut f = Context::new(333, 343);
+        f.eval("16e fsqrt fnegate fabs".into()).unwrap();
+        assert_eq!(&[4.0], f.float_stack());
+    }
+
+    #[test]
+    fn float_rounding_words() {
+        let mut f = Context::new(333, 343);
+        f.eval("-1.5e floor 2.5e fround -2.5e fround 1.7e ftrunc".into())
+            .unwrap();
+        assert_eq!(&[-2.0, 2.0, -2.0, 1.0], f.float_stack());
+    }
+
+    #[test]
+    fn fmin_and_fmax_pick_smaller_and_larger() {
+        let mut f = Context::new(333, 343);
+        f.eval("1e 2e fmin 1e 2e fmax".into()).unwrap();
+        assert_eq!(&[1.0, 2.0], f.float_stack());
+    }
+
+    #[test]
+    fn fswap_and_fover_rearrange_floats() {
+        let mut f = Context::new(333, 343);
+        f.eval("1e 2e fswap fover".into()).unwrap();
+        assert_eq!(&[2.0, 1.0, 2.0], f.float_stack());
+    }
+
+    #[test]
+    fn frot_fdup_and_fdrop_rearrange_floats() {
+        let mut f = Context::new(333, 343);
+        f.eval("1e 2e 3e frot fdup fdrop".into()).unwrap();
+        assert_eq!(&[2.0, 3.0, 1.0], f.float_stack());
+    }
+
+    #[test]
+    fn fdepth_counts_floats() {
+        let mut f = Context::new(333, 343);
+        f.eval("1e 2e fdepth".into()).unwrap();
+        assert_eq!(&[1.0, 2.0], f.float_stack());
+        assert_eq!(&[2], f.stack());
+    }
+
+    #[test]
+    fn float_comparisons_push_flags() {
+        let mut f = Context::new(333, 343);
+        f.eval("0e f0= 1e f0= -1e f0< 1e 2e f<".into()).unwrap();
+        assert!(f.float_stack().is_empty());
+        assert_eq!(&[-1, 0, -1, -1], f.stack());
+    }
+
+    #[test]
+    fn s_to_f_and_f_to_s_convert_singles() {
+        let mut f = Context::new(333, 343);
+        f.eval("7 s>f -2.9e f>s".into()).unwrap();
+        assert_eq!(&[7.0], f.float_stack());
+        assert_eq!(&[-2], f.stack());
+    }
+
+    #[test]
+    fn d_to_f_and_f_to_d_convert_doubles() {
+        let mut f = Context::new(333, 343);
+        f.eval("5000000000. d>f 1e10 f>d".into()).unwrap();
+        assert_eq!(&[5e9], f.float_stack());
+        assert_eq!(&[1410065408, 2], f.stack());
+    }
+
+    #[test]
+    fn floats_and_float_plus_scale_addresses() {
+        let mut f = Context::new(333, 343);
+        f.eval("2 floats 1 float+ 3 faligned".into()).unwrap();
+        assert!(f.float_stack().is_empty());
+        assert_eq!(&[16, 9, 8], f.stack());
+    }
+
+    #[test]
+    fn faligned_wraps_negative_addresses() {
+        let mut f = Context::new(333, 343);
+        f.eval("-1 faligned -9 faligned 9 faligned".into()).unwrap();
+        assert_eq!(&[0, -8, 16], f.stack());
+    }
+
+    #[test]
+    fn floats_are_stored_in_data_space() {
+        let mut f = Context::new(333, 343);
+        f.eval("fvariable x 2.5e x f! x f@ x faligned x =".into())
+            .unwrap();
+        assert_eq!(&[2.5], f.float_stack());
+        assert_eq!(&[-1], f.stack());
+
+        f.eval("1e fconstant one : two one one f+ ; two 3e here f, f@".into())
+            .unwrap();
+        assert_eq!(&[2.5, 2.0, 3.0], f.float_stack());
+
+        f.reset();
+        f.eval("4e : four fliteral ; four four".into()).unwrap();
+        assert_eq!(&[4.0, 4.0], f.float_stack());
+    }
+
+    #[test]
+    fn float_stack_errors_are_reported() {
+        let mut f = Context::new(3, 343);
+        assert_eq!(
+            ContextErr::FloatStackErr(stack::StackErr::Underflow),
+            f.eval("f.".into()).unwrap_err()
+        );
+        assert_eq!(
+            ContextErr::FloatStackErr(stack::StackErr::Overflow),
+            f.eval("1e 2e 3e 4e".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn double_words_follow_overflow_policy() {
+        let settings = Settings {
+            overflow: Overflow::Checked,
+            ..Settings::default()
+        };
+        let mut f = Context::<Datum>::with_settings(333, 343, 1024, settings);
+        assert_eq!(
+            ContextErr::ArithmeticOverflow,
+            f.eval("9223372036854775807. 1. d+".into()).unwrap_err()
+        );
+        assert_eq!(
+            ContextErr::ArithmeticOverflow,
+            f.eval("0 2 2 um/mod".into()).unwrap_err()
+        );
+        assert_eq!(
+            ContextErr::DivideByZero,
+            f.eval("1. 0 um/mod".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn two_store_and_two_fetch_move_cell_pairs() {
+        let mut f = Context::new(333, 343);
+        f.eval("2variable p 1 2 p 2! p 2@ p @".into()).unwrap();
+        assert_eq!(&[1, 2, 2], f.stack());
+    }
+
+    #[test]
+    fn test_mul_multiplies() {
+        let mut f = Context::new(333, 343);
+        f.eval("4 7 *".into()).unwrap();
+        assert_eq!(28, f.stack()[0]);
+
+        f.eval("-9 *".into()).unwrap();
+        assert_eq!(-252, f.stack()[0]);
+    }
+
+    #[test]
+    fn test_sub_subtracts() {
+        let mut f = Context::new(333, 343);
+        f.eval("2 1 -".into()).unwrap();
+        assert_eq!(1, f.stack()[0]);
+
+        f.eval("11 -".into()).unwrap();
+        assert_eq!(-10, f.stack()[0]);
+    }
+
+    #[test]
+    fn reversed_operand_order_is_opt_in() {
+        let settings = Settings {
+            operand_order: OperandOrder::Reversed,
+            ..Settings::default()
+        };
+        let mut f = Context::with_settings(333, 343, 1024, settings);
+        f.eval("1 2 - 2 8 /".into()).unwrap();
+        assert_eq!(&[1, 4], f.stack());
+
+        let mut f = Context::new(333, 343);
+        f.eval("1 2 - 8 2 /".into()).unwrap();
+        assert_eq!(&[-1, 4], f.stack());
+    }
+
+    #[test]
+    fn test_plus_adds() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 2 +".into()).unwrap();
+        assert_eq!(3, f.stack()[0]);
+
+        f.eval("1 +".into()).unwrap();
+        assert_eq!(4, f.stack()[0]);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_DUP_duplicates_top_of_stack() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 DUP".into()).unwrap();
+        assert_eq!(1, f.stack()[0]);
+        assert_eq!(1, f.stack()[1]);
+    }
+
+    #[test]
+    fn lookup_ignores_case_by_default() {
+        let mut f = Context::new(333, 343);
+        f.eval(": Square DUP * ; 3 square 4 SQUARE 1 Constant One one".into())
+            .unwrap();
+        assert_eq!(&[9, 16, 1], f.stack());
+
+        f.eval(": SQUARE drop 0 ; 5 square".into()).unwrap();
+        assert_eq!(&[9, 16, 1, 0], f.stack());
+
+        let (newest, _) = f.dictionary().last().unwrap();
+        let (older, _) = f.dictionary()[f.dictionary().len() - 3];
+        assert_eq!(*newest, older);
+    }
+
+    #[test]
+    fn variables_are_stable_across_redefinitions() {
+        let mut f = Context::new(333, 343);
+        f.eval("variable x 5 x ! : get-x x @ ; variable y 6 y !".into())
+            .unwrap();
+        f.eval("variable x 7 x ! : get-y y @ ; variable y 8 y !".into())
+            .unwrap();
+        f.eval("get-x x @ get-y y @".into()).unwrap();
+        assert_eq!(&[5, 7, 6, 8], f.stack());
+    }
+
+    #[test]
+    fn redefinition_shadows_without_moving_entries() {
+        let mut f = Context::new(333, 343);
+        f.eval(": a 1 ; : b 2 ; : use-a a ;".into()).unwrap();
+        let before: Vec<_> = f.dictionary().iter().map(|(key, _)| *key).collect();
+
+        f.eval(": a 3 ; a use-a b".into()).unwrap();
+        assert_eq!(&[3, 1, 2], f.stack());
+
+        let after: Vec<_> = f.dictionary().iter().map(|(key, _)| *key).collect();
+        assert_eq!(before[..], after[..before.len()]);
+        assert_eq!(before.len() + 1, after.len());
+    }
+
+    #[test]
+    fn long_names_are_distinct() {
+        let mut f = Context::new(333, 343);
+        f.eval(": compute-average-latency 1 ; : compute-average-latency-ms 2 ;".into())
+            .unwrap();
+        f.eval("compute-average-latency compute-average-latency-ms".into())
+            .unwrap();
+        assert_eq!(&[1, 2], f.stack());
+    }
+
+    #[test]
+    fn words_lists_names_newest_first() {
+        let mut f = Context::new(333, 343);
+        f.eval(": First-Word ; variable a-rather-long-variable-name words".into())
+            .unwrap();
+        assert!(f
+            .take_output()
+            .starts_with("a-rather-long-variable-name First-Word "));
+
+        let (key, _) = f.dictionary().last().unwrap();
+        assert_eq!("a-rather-long-variable-name", f.name(key.unwrap()));
+
+        let (key, _) = f.dictionary()[f.dictionary().len() - 2];
+        assert_eq!("First-Word", f.name(key.unwrap()));
+    }
+
+    #[test]
+    fn strict_case_matches_exactly() {
+        let settings = Settings {
+            case: Case::Sensitive,
+            ..Settings::default()
+        };
+        let mut f = Context::<Datum>::with_settings(333, 343, 1024, settings);
+        f.eval(": Foo 1 ; : foo 2 ; Foo foo dup".into()).unwrap();
+        assert_eq!(&[1, 2, 2], f.stack());
+        assert!(matches!(
+            f.eval("DUP".into()).unwrap_err(),
+            ContextErr::Parse(_)
+        ));
+        assert!(matches!(
+            f.eval("BYE".into()).unwrap_err(),
+            ContextErr::Parse(_)
+        ));
+    }
+
+    #[test]
+    fn yield_returns_err() {
+        let mut f = Context::new(333, 343);
+        assert_eq!(
+            ContextErr::YieldUnsupported,
+            f.eval("yield".into()).unwrap_err()
+        );
+        assert_eq!(
+            ContextErr::YieldUnsupported,
+            f.eval("YIELD".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn bye_ignores_case_by_default() {
+        let mut f = Context::new(333, 343);
+        assert_eq!(Return::Shutdown, f.eval("BYE".into()).unwrap());
+        assert_eq!(Return::Shutdown, f.eval("Bye".into()).unwrap());
+    }
+
+    #[test]
+    fn swap_exchanges_top_two_items() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 2 swap".into()).unwrap();
+        assert_eq!(&[2, 1], f.stack());
+    }
+
+    #[test]
+    fn over_copies_second_item() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 2 over".into()).unwrap();
+        assert_eq!(&[1, 2, 1], f.stack());
+    }
+
+    #[test]
+    fn rot_brings_third_item_to_top() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 2 3 rot".into()).unwrap();
+        assert_eq!(&[2, 3, 1], f.stack());
+    }
+
+    #[test]
+    fn minus_rot_buries_top_item() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 2 3 -rot".into()).unwrap();
+        assert_eq!(&[3, 1, 2], f.stack());
+    }
+
+    #[test]
+    fn nip_drops_second_item() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 2 nip".into()).unwrap();
+        assert_eq!(&[2], f.stack());
+    }
+
+    #[test]
+    fn tuck_copies_top_below_second() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 2 tuck".into()).unwrap();
+        assert_eq!(&[2, 1, 2], f.stack());
+    }
+
+    #[test]
+    fn pick_copies_item_at_depth() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 2 3 2 pick 0 pick".into()).unwrap();
+        assert_eq!(&[1, 2, 3, 1, 1], f.stack());
+    }
+
+    #[test]
+    fn roll_moves_item_at_depth_to_top() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 2 3 2 roll 0 roll".into()).unwrap();
+        assert_eq!(&[2, 3, 1], f.stack());
+    }
+
+    #[test]
+    fn question_dup_duplicates_only_non_zero() {
+        let mut f = Context::new(333, 343);
+        f.eval("0 ?dup 1 ?dup".into()).unwrap();
+        assert_eq!(&[0, 1, 1], f.stack());
+    }
+
+    #[test]
+    fn depth_counts_items() {
+        let mut f = Context::new(333, 343);
+        f.eval("depth 7 depth".into()).unwrap();
+        assert_eq!(&[0, 7, 2], f.stack());
+    }
+
+    #[test]
+    fn two_dup_duplicates_pair() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 2 2dup".into()).unwrap();
+        assert_eq!(&[1, 2, 1, 2], f.stack());
+    }
+
+    #[test]
+    fn two_drop_drops_pair() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 2 3 2drop".into()).unwrap();
+        assert_eq!(&[1], f.stack());
+    }
+
+    #[test]
+    fn two_swap_exchanges_pairs() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 2 3 4 2swap".into()).unwrap();
+        assert_eq!(&[3, 4, 1, 2], f.stack());
+    }
+
+    #[test]
+    fn two_over_copies_second_pair() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 2 3 4 2over".into()).unwrap();
+        assert_eq!(&[1, 2, 3, 4, 1, 2], f.stack());
+    }
+
+    #[test]
+    fn stack_words_report_underflow_and_overflow() {
+        let mut f = Context::new(3, 343);
+        assert_eq!(
+            ContextErr::StackErr(stack::StackErr::Underflow),
+            f.eval("1 swap".into()).unwrap_err()
+        );
+
+        f.reset();
+        assert_eq!(
+            ContextErr::StackErr(stack::StackErr::Underflow),
+            f.eval("1 2 5 pick".into()).unwrap_err()
+        );
+
+        f.reset();
+        assert_eq!(
+            ContextErr::StackErr(stack::StackErr::Overflow),
+            f.eval("1 2 2dup".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn true_and_false_are_well_formed_flags() {
+        let mut f = Context::new(333, 343);
+        f.eval("true false".into()).unwrap();
+        assert_eq!(&[-1, 0], f.stack());
+    }
+
+    #[test]
+    fn equals_compares_for_equality() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 1 = 1 2 =".into()).unwrap();
+        assert_eq!(&[-1, 0], f.stack());
+    }
+
+    #[test]
+    fn not_equals_compares_for_inequality() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 2 <> 2 2 <>".into()).unwrap();
+        assert_eq!(&[-1, 0], f.stack());
+    }
+
+    #[test]
+    fn less_than_compares_signed() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 2 < 2 1 < -1 1 <".into()).unwrap();
+        assert_eq!(&[-1, 0, -1], f.stack());
+    }
+
+    #[test]
+    fn greater_than_compares_signed() {
+        let mut f = Context::new(333, 343);
+        f.eval("2 1 > 1 2 > 1 -1 >".into()).unwrap();
+        assert_eq!(&[-1, 0, -1], f.stack());
+    }
+
+    #[test]
+    fn zero_equals_tests_for_zero() {
+        let mut f = Context::new(333, 343);
+        f.eval("0 0= 5 0= 5 0<> 0 0<>".into()).unwrap();
+        assert_eq!(&[-1, 0, -1, 0], f.stack());
+    }
+
+    #[test]
+    fn zero_less_and_greater_test_sign() {
+        let mut f = Context::new(333, 343);
+        f.eval("-5 0< 5 0< 5 0> -5 0>".into()).unwrap();
+        assert_eq!(&[-1, 0, -1, 0], f.stack());
+    }
+
+    #[test]
+    fn unsigned_comparisons_reinterpret_sign_bit() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 -1 u< -1 1 u< -1 1 u> 1 2 u<".into()).unwrap();
+        assert_eq!(&[-1, 0, -1, -1], f.stack());
+    }
+
+    #[test]
+    fn and_combines_bits() {
+        let mut f = Context::new(333, 343);
+        f.eval("12 10 and".into()).unwrap();
+        assert_eq!(&[8], f.stack());
+    }
+
+    #[test]
+    fn or_combines_bits() {
+        let mut f = Context::new(333, 343);
+        f.eval("12 10 or".into()).unwrap();
+        assert_eq!(&[14], f.stack());
+    }
+
+    #[test]
+    fn xor_combines_bits() {
+        let mut f = Context::new(333, 343);
+        f.eval("12 10 xor".into()).unwrap();
+        assert_eq!(&[6], f.stack());
+    }
+
+    #[test]
+    fn invert_flips_every_bit() {
+        let mut f = Context::new(333, 343);
+        f.eval("0 invert 5 invert".into()).unwrap();
+        assert_eq!(&[-1, -6], f.stack());
+    }
+
+    #[test]
+    fn lshift_shifts_out_high_bits() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 4 lshift 1 32 lshift".into()).unwrap();
+        assert_eq!(&[16, 0], f.stack());
+    }
+
+    #[test]
+    fn rshift_shifts_in_zero_bits() {
+        let mut f = Context::new(333, 343);
+        f.eval("16 4 rshift -1 28 rshift -1 32 rshift".into())
+            .unwrap();
+        assert_eq!(&[1, 15, 0], f.stack());
+    }
+
+    #[test]
+    fn and_combines_flags() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 2 < 3 4 < and".into()).unwrap();
+        assert_eq!(&[-1], f.stack());
+    }
+
+    #[test]
+    fn test_bye_returns_exist() {
+        assert_eq!(true, false);
+    }
+
+    #[test]
+    fn colon_defines_word() {
+        let mut f = Context::new(333, 343);
+        f.eval(": add-three 1 2 + + ;".into()).unwrap();
+        f.eval("4 add-three".into()).unwrap();
+        assert_eq!(&[7], f.stack());
+    }
+
+    #[test]
+    fn colon_definition_may_span_lines() {
+        let mut f = Context::new(333, 343);
+        f.eval(": square".into()).unwrap();
+        assert_eq!(Mode::Compiling, f.mode);
+
+        f.eval("dup *".into()).unwrap();
+        f.eval(";".into()).unwrap();
+        assert_eq!(Mode::Interpreting, f.mode);
+
+        f.eval("5 square".into()).unwrap();
+        assert_eq!(&[25], f.stack());
+    }
+
+    #[test]
+    fn colon_definition_calls_other_definitions() {
+        let mut f = Context::new(333, 343);
+        f.eval(": square dup * ; : fourth square square ;".into())
+            .unwrap();
+        f.eval("2 fourth".into()).unwrap();
+        assert_eq!(&[16], f.stack());
+    }
+
+    #[test]
+    fn colon_does_not_execute_body() {
+        let mut f = Context::new(333, 343);
+        f.eval(": pushes 1 2 3 ;".into()).unwrap();
+        assert!(f.stack().is_empty());
+    }
+
+    #[test]
+    fn semicolon_while_interpreting_returns_err() {
+        let mut f = Context::new(333, 343);
+        assert_eq!(
+            ContextErr::CompileOnly(";".into()),
+            f.eval(";".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn nested_colon_returns_err() {
+        let mut f = Context::new(333, 343);
+        assert_eq!(
+            ContextErr::NestedDefinition,
+            f.eval(": a : b ;".into()).unwrap_err()
+        );
+        assert_eq!(Mode::Interpreting, f.mode);
+    }
+
+    #[test]
+    fn failed_definition_is_discarded() {
+        let mut f = Context::new(333, 343);
+        assert!(f.eval(": broken 1 not-a-word ;".into()).is_err());
+        assert_eq!(Mode::Interpreting, f.mode);
+        assert!(f.find_word("broken").is_none());
+    }
+
+    #[test]
+    fn colon_compiles_threaded_code() {
+        let mut f = Context::new(333, 343);
+        f.eval(": inc 1 + ;".into()).unwrap();
+
+        match &*f.find_word("inc").unwrap() {
+            Word::Custom { body } => {
+                assert_eq!(3, body.len());
+                assert!(matches!(body[0], Instruction::Literal(1)));
+                assert!(matches!(body[1], Instruction::Call(_)));
+                assert!(matches!(body[2], Instruction::Exit));
+            }
+            word => panic!("Expected custom word, got {:?}", word),
+        }
+    }
+
+    #[test]
+    fn exit_returns_early() {
+        let mut f = Context::new(333, 343);
+        f.eval(": early 1 exit 2 ;".into()).unwrap();
+        f.eval("early 3".into()).unwrap();
+        assert_eq!(&[1, 3], f.stack());
+    }
+
+    #[test]
+    fn exit_while_interpreting_returns_err() {
+        let mut f = Context::new(333, 343);
+        assert_eq!(
+            ContextErr::CompileOnly("exit".into()),
+            f.eval("exit".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn nested_words_do_not_recurse_on_native_stack() {
+        let mut f = Context::new(333, 343);
+        f.eval(": a 1 ; : b a a + ; : c b b + 1 ;".into()).unwrap();
+        f.eval("c".into()).unwrap();
+        assert_eq!(&[4, 1], f.stack());
+        assert!(f.return_stack.data().is_empty());
+    }
+
+    #[test]
+    fn unbounded_recursion_overflows_return_stack() {
+        let mut f = Context::new(333, 343);
+        f.eval(": forever recurse 1 ;".into()).unwrap();
+        assert_eq!(
+            ContextErr::ReturnStackErr(stack::StackErr::Overflow),
+            f.eval("forever".into()).unwrap_err()
+        );
+        assert!(f.return_stack.data().is_empty());
+    }
+
+    #[test]
+    fn to_r_and_r_from_move_values() {
+        let mut f = Context::new(333, 343);
+        f.eval(": stash >r 2 r@ r> ;".into()).unwrap();
+        f.eval("1 stash".into()).unwrap();
+        assert_eq!(&[2, 1, 1], f.stack());
+    }
+
+    #[test]
+    fn r_from_on_return_address_returns_err() {
+        let mut f = Context::new(333, 343);
+        f.eval(": inner r> ; : outer inner 1 ;".into()).unwrap();
+        assert_eq!(
+            ContextErr::ReturnStackMismatch,
+            f.eval("outer".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn if_then_runs_body_on_true() {
+        let mut f = Context::new(333, 343);
+        f.eval(": maybe if 10 then 1 ;".into()).unwrap();
+        f.eval("-1 maybe 0 maybe".into()).unwrap();
+        assert_eq!(&[10, 1, 1], f.stack());
+    }
+
+    #[test]
+    fn if_else_then_picks_branch() {
+        let mut f = Context::new(333, 343);
+        f.eval(": pick if 1 else 2 then 3 ;".into()).unwrap();
+        f.eval("-1 pick 0 pick".into()).unwrap();
+        assert_eq!(&[1, 3, 2, 3], f.stack());
+    }
+
+    #[test]
+    fn if_nests() {
+        let mut f = Context::new(333, 343);
+        f.eval(": nest if if 1 else 2 then else 3 then ;".into())
+            .unwrap();
+        f.eval("-1 -1 nest 0 -1 nest 0 nest".into()).unwrap();
+        assert_eq!(&[1, 2, 3], f.stack());
+    }
+
+    #[test]
+    fn tail_recursion_does_not_grow_return_stack() {
+        let mut f = Context::new(333, 343);
+        f.eval(": countdown dup if 1 - recurse then ;".into())
+            .unwrap();
+        f.eval("10000 countdown".into()).unwrap();
+        assert_eq!(&[0], f.stack());
+    }
+
+    #[test]
+    fn unbalanced_if_returns_err() {
+        let mut f = Context::new(333, 343);
+        assert_eq!(
+            ContextErr::UnbalancedControlFlow(";".into()),
+            f.eval(": open if 1 ;".into()).unwrap_err()
+        );
+        assert_eq!(
+            ContextErr::UnbalancedControlFlow("then".into()),
+            f.eval(": closed 1 then ;".into()).unwrap_err()
+        );
+        assert_eq!(
+            ContextErr::UnbalancedControlFlow("else".into()),
+            f.eval(": closed 1 else ;".into()).unwrap_err()
+        );
+        assert_eq!(
+            ContextErr::CompileOnly("if".into()),
+            f.eval("1 if".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn reset_discards_unfinished_control_flow() {
+        let mut f = Context::new(333, 343);
+        f.eval(": foo if".into()).unwrap();
+        f.reset();
+        f.eval(": bar 1 ; bar".into()).unwrap();
+        assert_eq!(&[1], f.stack());
+
+        f.eval(": baz 3 0 do".into()).unwrap();
+        f.reset();
+        f.eval(": qux 2 ; qux".into()).unwrap();
+        assert_eq!(&[2], f.stack());
+    }
+
+    #[test]
+    fn do_loop_counts_up_to_limit() {
+        let mut f = Context::new(333, 343);
+        f.eval(": sum 0 10 0 do i + loop ;".into()).unwrap();
+        f.eval("sum".into()).unwrap();
+        assert_eq!(&[45], f.stack());
+        assert!(f.return_stack.data().is_empty());
+    }
+
+    #[test]
+    fn question_do_skips_empty_loop() {
+        let mut f = Context::new(333, 343);
+        f.eval(": skip 5 5 ?do i loop ;".into()).unwrap();
+        f.eval("skip".into()).unwrap();
+        assert!(f.stack().is_empty());
+        assert!(f.return_stack.data().is_empty());
+    }
+
+    #[test]
+    fn plus_loop_counts_down_through_limit() {
+        let mut f = Context::new(333, 343);
+        f.eval(": down 0 10 do i -2 +loop ;".into()).unwrap();
+        f.eval("down".into()).unwrap();
+        assert_eq!(&[10, 8, 6, 4, 2, 0], f.stack());
+    }
+
+    #[test]
+    fn j_reads_outer_loop_index() {
+        let mut f = Context::new(333, 343);
+        f.eval(": grid 2 0 do 3 0 do j 10 * i + loop loop ;".into())
+            .unwrap();
+        f.eval("grid".into()).unwrap();
+        assert_eq!(&[0, 1, 2, 10, 11, 12], f.stack());
+    }
+
+    #[test]
+    fn leave_inside_if_exits_loop() {
+        let mut f = Context::new(333, 343);
+        f.eval(": upto 10 0 do i dup -3 + if else leave then loop 99 ;".into())
+            .unwrap();
+        f.eval("upto".into()).unwrap();
+        assert_eq!(&[0, 1, 2, 3, 99], f.stack());
+        assert!(f.return_stack.data().is_empty());
+    }
+
+    #[test]
+    fn unloop_allows_exit_from_loop() {
+        let mut f = Context::new(333, 343);
+        f.eval(": first 10 5 do i unloop exit loop 99 ;".into())
+            .unwrap();
+        f.eval("first".into()).unwrap();
+        assert_eq!(&[5], f.stack());
+        assert!(f.return_stack.data().is_empty());
+    }
+
+    #[test]
+    fn unbalanced_loop_returns_err() {
+        let mut f = Context::new(333, 343);
+        assert_eq!(
+            ContextErr::UnbalancedControlFlow("loop".into()),
+            f.eval(": bad 1 loop ;".into()).unwrap_err()
+        );
+        assert_eq!(
+            ContextErr::UnbalancedControlFlow("loop".into()),
+            f.eval(": bad 10 0 do if loop ;".into()).unwrap_err()
+        );
+        assert_eq!(
+            ContextErr::UnbalancedControlFlow("leave".into()),
+            f.eval(": bad leave ;".into()).unwrap_err()
+        );
+        assert_eq!(
+            ContextErr::UnbalancedControlFlow(";".into()),
+            f.eval(": bad 10 0 do ;".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn begin_until_loops_until_true() {
+        let mut f = Context::new(333, 343);
+        f.eval(": count-down begin dup 1 - dup 0= until ;".into())
+            .unwrap();
+        f.eval("3 count-down".into()).unwrap();
+        assert_eq!(&[3, 2, 1, 0], f.stack());
+    }
+
+    #[test]
+    fn begin_while_repeat_tests_before_body() {
+        let mut f = Context::new(333, 343);
+        f.eval(": drain begin dup while 1 - repeat 99 ;".into())
+            .unwrap();
+        f.eval("3 drain 0 drain".into()).unwrap();
+        assert_eq!(&[0, 99, 0, 99], f.stack());
+    }
+
+    #[test]
+    fn begin_again_loops_until_exit() {
+        let mut f = Context::new(333, 343);
+        f.eval(": forever begin 1 - dup 0= if exit then again ;".into())
+            .unwrap();
+        f.eval("5 forever".into()).unwrap();
+        assert_eq!(&[0], f.stack());
+    }
+
+    #[test]
+    fn broken_indefinite_loops_return_err() {
+        let mut f = Context::new(333, 343);
+        assert_eq!(
+            ContextErr::UnbalancedControlFlow("until".into()),
+            f.eval(": bad 1 until ;".into()).unwrap_err()
+        );
+        assert_eq!(
+            ContextErr::UnbalancedControlFlow("repeat".into()),
+            f.eval(": bad begin 1 repeat ;".into()).unwrap_err()
+        );
+        assert_eq!(
+            ContextErr::UnbalancedControlFlow("again".into()),
+            f.eval(": bad 1 if again ;".into()).unwrap_err()
+        );
+        assert_eq!(
+            ContextErr::UnbalancedControlFlow(";".into()),
+            f.eval(": bad begin ;".into()).unwrap_err()
+        );
+        assert_eq!(
+            ContextErr::CompileOnly("begin".into()),
+            f.eval("begin".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn create_pushes_data_field_address() {
+        let mut f = Context::new(333, 343);
+        f.eval("here create x x".into()).unwrap();
+        assert_eq!(f.stack()[0], f.stack()[1]);
+
+        f.reset();
+        f.eval("create x 1 cells allot 5 x ! x @".into()).unwrap();
+        assert_eq!(&[5], f.stack());
+    }
+
+    #[test]
+    fn create_without_name_returns_err() {
+        let mut f = Context::new(333, 343);
+        assert_eq!(
+            ContextErr::MissingName,
+            f.eval("create   ".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn does_attaches_behaviour_to_created_word() {
+        let mut f = Context::new(333, 343);
+        f.eval(": ten create does> drop 10 ;".into()).unwrap();
+        f.eval("ten a a".into()).unwrap();
+        assert_eq!(&[10], f.stack());
+
+        f.eval(": use-a a 1 + ; use-a".into()).unwrap();
+        assert_eq!(&[10, 11], f.stack());
+    }
+
+    #[test]
+    fn does_runs_with_data_field_address() {
+        let mut f = Context::new(333, 343);
+        f.eval(": const create , does> @ ;".into()).unwrap();
+        f.eval("7 const seven 9 const nine seven nine".into())
+            .unwrap();
+        assert_eq!(&[7, 9], f.stack());
+    }
+
+    #[test]
+    fn does_without_create_returns_err() {
+        let mut f = Context::new(333, 343);
+        f.eval(": orphan does> 1 ;".into()).unwrap();
+        assert_eq!(
+            ContextErr::DoesWithoutCreate,
+            f.eval("orphan".into()).unwrap_err()
+        );
+        assert_eq!(
+            ContextErr::CompileOnly("does>".into()),
+            f.eval("does>".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn comma_and_allot_fill_data_space() {
+        let mut f = Context::new(333, 343);
+        f.eval("create table 10 , 20 , 30 ,".into()).unwrap();
+        f.eval("table @ table cell+ @ table 2 cells + @".into())
+            .unwrap();
+        assert_eq!(&[10, 20, 30], f.stack());
+
+        f.eval("here 3 allot here".into()).unwrap();
+        assert_eq!(f.stack()[3] + 3, f.stack()[4]);
+    }
+
+    #[test]
+    fn byte_access_reads_and_writes_single_chars() {
+        let mut f = Context::new(333, 343);
+        f.eval("create bytes 65 c, 66 c, 300 c,".into()).unwrap();
+        f.eval("bytes c@ bytes 1 + c@ bytes 2 + c@".into()).unwrap();
+        assert_eq!(&[65, 66, 44], f.stack());
+
+        f.eval("67 bytes c! bytes c@".into()).unwrap();
+        assert_eq!(67, f.stack()[3]);
+    }
+
+    #[test]
+    fn aligned_rounds_up_to_cell_boundary() {
+        let mut f = Context::new(333, 343);
+        f.eval("0 aligned 1 aligned 4 aligned 5 aligned".into())
+            .unwrap();
+        assert_eq!(&[0, 4, 4, 8], f.stack());
+    }
+
+    #[test]
+    fn aligned_wraps_negative_addresses() {
+        let mut f = Context::new(333, 343);
+        f.eval("-1 aligned -5 aligned 2147483647 aligned".into())
+            .unwrap();
+        assert_eq!(&[0, -4, i32::MIN], f.stack());
+    }
+
+    #[test]
+    fn cells_are_little_endian_bytes() {
+        let mut f = Context::new(333, 343);
+        f.eval("create x 258 , x c@ x 1 + c@".into()).unwrap();
+        assert_eq!(&[2, 1], f.stack());
+    }
+
+    #[test]
+    fn access_outside_data_space_returns_err() {
+        let mut f = Context::new(333, 343);
+        f.eval("create x 1 , here".into()).unwrap();
+        let here = f.pop().unwrap() as usize;
+        assert_eq!(
+            ContextErr::AccessedUndefinedAtAddr(here),
+            f.eval("here @".into()).unwrap_err()
+        );
+        assert_eq!(
+            ContextErr::AccessedUndefinedAtAddr(here - 2),
+            f.eval("1 here 2 - !".into()).unwrap_err()
+        );
+        assert_eq!(
+            ContextErr::DataSpaceErr(data_space::DataSpaceErr::Overflow),
+            f.eval("1000000 allot".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn data_space_capacity_is_set_separately() {
+        let mut f = Context::<Datum>::with_settings(333, 343, 8192, Settings::default());
+        f.eval("create buf 3000 allot 1 buf 2999 + c! buf 2999 + c@".into())
+            .unwrap();
+        assert_eq!(&[1], f.stack());
+    }
+
+    #[test]
+    fn reserved_buffers_leave_capacity_for_user() {
+        let mut f = Context::new(333, 343);
+        f.eval("create buf 1372 allot".into()).unwrap();
+        assert_eq!(
+            ContextErr::DataSpaceErr(data_space::DataSpaceErr::Overflow),
+            f.eval("1 allot".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn variable() {
+        let mut f = Context::new(333, 343);
+
+        f.eval("variable balance 123 balance ! balance @".into())
+            .unwrap();
+
+        assert_eq!(f.stack()[0], 123);
+    }
+
+    #[test]
+    fn variables_do_not_overlap() {
+        let mut f = Context::new(333, 343);
+        f.eval("variable a variable b 1 a ! 2 b ! a @ b @".into())
+            .unwrap();
+        assert_eq!(&[1, 2], f.stack());
+    }
+
+    #[test]
+    fn two_variable_holds_two_cells() {
+        let mut f = Context::new(333, 343);
+        f.eval("2variable pair 3 pair ! 4 pair cell+ ! pair @ pair cell+ @".into())
+            .unwrap();
+        assert_eq!(&[3, 4], f.stack());
+    }
+
+    #[test]
+    fn constant_pushes_value() {
+        let mut f = Context::new(333, 343);
+        f.eval("42 constant answer 1 2 2constant pair answer pair".into())
+            .unwrap();
+        assert_eq!(&[42, 1, 2], f.stack());
+    }
+
+    #[test]
+    fn to_changes_value() {
+        let mut f = Context::new(333, 343);
+        f.eval("10 value limit limit 20 to limit limit".into())
+            .unwrap();
+        assert_eq!(&[10, 20], f.stack());
+
+        f.eval(": reset-limit 0 to limit ; reset-limit limit".into())
+            .unwrap();
+        assert_eq!(&[10, 20, 0], f.stack());
+    }
+
+    #[test]
+    fn to_non_value_returns_err() {
+        let mut f = Context::new(333, 343);
+        f.eval("1 constant one".into()).unwrap();
+        assert_eq!(
+            ContextErr::NotAValue("one".into()),
+            f.eval("2 to one".into()).unwrap_err()
+        );
+        assert_eq!(
+            ContextErr::NotAValue("missing".into()),
+            f.eval("2 to missing".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn marker_restores_dictionary_and_here() {
+        let mut f = Context::new(333, 343);
+        f.eval(": square dup * ; here marker checkpoint".into())
+            .unwrap();
+        let words = f.dictionary().len();
+
+        f.eval("variable x 100 allot : cube dup square * ; : square drop 0 ;".into())
+            .unwrap();
+        f.eval("checkpoint here 3 square".into()).unwrap();
+
+        let here = f.stack()[0];
+        assert_eq!(&[here, here, 9], f.stack());
+        assert_eq!(words - 1, f.dictionary().len());
+        assert!(f.eval("cube".into()).is_err());
+        assert!(f.eval("checkpoint".into()).is_err());
+    }
+
+    #[test]
+    fn forget_removes_word_and_later_words() {
+        let mut f = Context::new(333, 343);
+        f.eval(": one 1 ; here : two 2 ; 3 constant three".into())
+            .unwrap();
+        f.eval("forget TWO here one".into()).unwrap();
+        assert_eq!(&[f.stack()[0], f.stack()[0], 1], f.stack());
+        assert!(f.eval("two".into()).is_err());
+        assert!(f.eval("three".into()).is_err());
+    }
+
+    #[test]
+    fn forget_reveals_shadowed_definition() {
+        let mut f = Context::new(333, 343);
+        f.eval(": greet 1 ; : greet 2 ; forget greet greet".into())
+            .unwrap();
+        assert_eq!(&[1], f.stack());
+    }
+
+    #[test]
+    fn forget_builtin_or_undefined_returns_err() {
+        let mut f = Context::new(333, 343);
+        assert_eq!(
+            ContextErr::Protected("dup".into()),
+            f.eval("forget dup".into()).unwrap_err()
+        );
+        assert_eq!(
+            ContextErr::UndefinedWord("missing".into()),
+            f.eval("forget missing".into()).unwrap_err()
+        );
+        f.eval("1 dup".into()).unwrap();
+        assert_eq!(&[1, 1], f.stack());
+    }
+
+    #[test]
+    fn wide_cells_hold_large_values() {
+        let mut f = Context::<i64>::with_settings(333, 343, 1024, Settings::default());
+        f.eval("4294967296 3 * variable x x ! x @ 1 cells".into())
+            .unwrap();
+        assert_eq!(&[12884901888, 8], f.stack());
+
+        f.eval("2drop 9223372036854775807 1 + -1 1 rshift 1 -1 um*".into())
+            .unwrap();
+        assert_eq!(&[i64::MIN, i64::MAX, -1, 0], f.stack());
+    }
+
+    #[test]
+    fn wide_allot_past_address_range_returns_err() {
+        let mut f = Context::<i64>::with_settings(333, 343, 1024, Settings::default());
+        assert_eq!(
+            ContextErr::DataSpaceErr(data_space::DataSpaceErr::Overflow),
+            f.eval("9223372036854775807 allot".into()).unwrap_err()
+        );
+        assert_eq!(
+            ContextErr::DataSpaceErr(data_space::DataSpaceErr::Underflow),
+            f.eval("-9223372036854775808 allot".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn narrow_cells_address_whole_data_space() {
+        let mut f = Context::<i16>::with_settings(333, 343, 100000, Settings::default());
+        f.eval("variable low 11 low ! 30000 allot 30000 allot".into())
+            .unwrap();
+        f.eval("variable high 99 high ! high @ low @ high 32767 u>".into())
+            .unwrap();
+        assert_eq!(&[99, 11, -1], f.stack());
+
+        assert_eq!(
+            ContextErr::DataSpaceErr(data_space::DataSpaceErr::Overflow),
+            f.eval("5536 allot".into()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn narrow_cells_follow_width() {
+        let mut f = Context::<i16>::with_settings(333, 343, 1024, Settings::default());
+        f.eval("32767 1 + 1 cells -1 1 rshift 100000.".into())
+            .unwrap();
+        assert_eq!(&[i16::MIN, 2, i16::MAX, -31072, 1], f.stack());
+
+        let settings = Settings {
+            overflow: Overflow::Checked,
+            ..Settings::default()
+        };
+        let mut f = Context::<i16>::with_settings(333, 343, 1024, settings);
+        assert_eq!(
+            ContextErr::ArithmeticOverflow,
+            f.eval("200 200 *".into()).unwrap_err()
+        );
+        assert_eq!(
+            ContextErr::Parse("32768".parse::<i16>().unwrap_err()),
+            f.eval("32768".into()).unwrap_err()
+        );
     }
 }
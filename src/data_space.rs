@@ -0,0 +1,148 @@
+/// A list of errors a data space operation may return.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DataSpaceErr {
+    Overflow,
+    Underflow,
+    UndefinedAccess(Addr),
+}
+
+pub type Addr = usize;
+
+/// A contiguous, byte addressable region of memory. Only the bytes below `here` may be accessed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataSpace {
+    data: Vec<u8>,
+    capacity: usize,
+}
+
+impl DataSpace {
+    /// Creates a new data space with the given capacity in bytes.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            data: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the address of the next free byte.
+    pub fn here(&self) -> Addr {
+        self.data.len()
+    }
+
+    /// Reserves the given number of zeroed bytes, or releases them if negative.
+    pub fn allot(&mut self, bytes: isize) -> Result<(), DataSpaceErr> {
+        let here = match (self.here() as isize).checked_add(bytes) {
+            Some(here) if here < 0 => return Err(DataSpaceErr::Underflow),
+            Some(here) => here,
+            None => return Err(DataSpaceErr::Overflow),
+        };
+
+        if here as usize > self.capacity {
+            return Err(DataSpaceErr::Overflow);
+        }
+
+        self.data.resize(here as usize, 0);
+
+        Ok(())
+    }
+
+    /// Appends the bytes at `here`, returning the address they were written to.
+    pub fn append(&mut self, bytes: &[u8]) -> Result<Addr, DataSpaceErr> {
+        let addr = self.here();
+
+        if addr + bytes.len() > self.capacity {
+            return Err(DataSpaceErr::Overflow);
+        }
+
+        self.data.extend_from_slice(bytes);
+
+        Ok(addr)
+    }
+
+    /// Returns the given number of bytes starting at the address.
+    pub fn fetch(&self, addr: Addr, len: usize) -> Result<&[u8], DataSpaceErr> {
+        match addr.checked_add(len) {
+            Some(end) if end <= self.data.len() => Ok(&self.data[addr..end]),
+            _ => Err(DataSpaceErr::UndefinedAccess(addr)),
+        }
+    }
+
+    /// Overwrites the bytes starting at the address.
+    pub fn store(&mut self, addr: Addr, bytes: &[u8]) -> Result<(), DataSpaceErr> {
+        match addr.checked_add(bytes.len()) {
+            Some(end) if end <= self.data.len() => {
+                self.data[addr..end].copy_from_slice(bytes);
+                Ok(())
+            }
+            _ => Err(DataSpaceErr::UndefinedAccess(addr)),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_sets_capacity() {
+        let cap = 30201;
+        let d = DataSpace::new(cap);
+        assert_eq!(cap, d.capacity);
+        assert_eq!(0, d.here());
+    }
+
+    #[test]
+    fn allot_moves_here_and_zeroes() {
+        let mut d = DataSpace::new(16);
+        d.allot(4).unwrap();
+        assert_eq!(4, d.here());
+        assert_eq!(&[0, 0, 0, 0], d.fetch(0, 4).unwrap());
+
+        d.allot(-3).unwrap();
+        assert_eq!(1, d.here());
+    }
+
+    #[test]
+    fn allot_out_of_bounds_returns_err() {
+        let mut d = DataSpace::new(4);
+        assert_eq!(DataSpaceErr::Overflow, d.allot(5).unwrap_err());
+        assert_eq!(DataSpaceErr::Underflow, d.allot(-1).unwrap_err());
+        assert_eq!(0, d.here());
+    }
+
+    #[test]
+    fn append_returns_addr() {
+        let mut d = DataSpace::new(4);
+        assert_eq!(0, d.append(&[1, 2]).unwrap());
+        assert_eq!(2, d.append(&[3]).unwrap());
+        assert_eq!(&[1, 2, 3], d.fetch(0, 3).unwrap());
+        assert_eq!(DataSpaceErr::Overflow, d.append(&[4, 5]).unwrap_err());
+    }
+
+    #[test]
+    fn store_overwrites() {
+        let mut d = DataSpace::new(4);
+        d.allot(4).unwrap();
+        d.store(1, &[7, 8]).unwrap();
+        assert_eq!(&[0, 7, 8, 0], d.fetch(0, 4).unwrap());
+    }
+
+    #[test]
+    fn access_past_here_returns_err() {
+        let mut d = DataSpace::new(16);
+        d.allot(4).unwrap();
+        assert_eq!(DataSpaceErr::UndefinedAccess(2), d.fetch(2, 4).unwrap_err());
+        assert_eq!(
+            DataSpaceErr::UndefinedAccess(4),
+            d.store(4, &[1]).unwrap_err()
+        );
+        assert_eq!(
+            DataSpaceErr::UndefinedAccess(usize::MAX),
+            d.fetch(usize::MAX, 4).unwrap_err()
+        );
+    }
+}
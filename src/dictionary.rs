@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum DictionaryErr {
     Overflow,
@@ -9,20 +12,23 @@ pub type Addr = usize;
 #[derive(Debug, Clone, PartialEq)]
 pub struct Dictionary<Key, Value>
 where
-    Key: PartialEq,
+    Key: Eq + Hash,
 {
     capacity: usize,
     data: Vec<(Option<Key>, Value)>,
+    /// The address of each key's entry in `data`, so lookups don't have to scan it.
+    index: HashMap<Key, Addr>,
 }
 impl<Key, Value> Dictionary<Key, Value>
 where
-    Key: PartialEq,
+    Key: Eq + Hash + Clone,
 {
     /// Creates a new dictionary with the given capacity
     pub fn new(capacity: usize) -> Self {
         Self {
             capacity,
             data: Vec::with_capacity(capacity),
+            index: HashMap::with_capacity(capacity),
         }
     }
 
@@ -31,54 +37,25 @@ where
         &self.data
     }
 
-    /// Inserts a new value at the given key, overwriting any previous keys.
+    /// Inserts a new value at the given key. An older value with the same key is shadowed rather than removed, so
+    /// the addresses of existing entries never change.
     pub fn insert(&mut self, key: Option<Key>, value: Value) -> Result<Addr, DictionaryErr> {
-        // Remove first item with the same key
-        let mut i = 0;
-
-        match key {
-            Some(ref key) => {
-                while i < self.data.len() {
-                    match &self.data[i].0 {
-                        Some(stored_key) => {
-                            if *stored_key == *key {
-                                self.data.remove(i);
-                                break;
-                            }
-                        }
-                        None => {}
-                    }
-
-                    i += 1;
-                }
-            }
-            None => {}
-        }
-
         let addr = self.data.len();
 
         if addr == self.capacity {
             return Err(DictionaryErr::Overflow);
         }
+
+        if let Some(ref key) = key {
+            self.index.insert(key.clone(), addr);
+        }
         self.data.push((key, value));
 
         Ok(addr)
     }
 
     pub fn get_addr(&self, key: Key) -> Option<usize> {
-        // TODO: test
-        for (i, (stored_key, _)) in self.data.iter().enumerate() {
-            match stored_key {
-                Some(stored_key) => {
-                    if key == *stored_key {
-                        return Some(i);
-                    }
-                }
-                None => {}
-            }
-        }
-
-        None
+        self.index.get(&key).copied()
     }
 
     pub fn set_from_addr(&mut self, addr: usize, value: Value) -> Result<(), DictionaryErr> {
@@ -91,36 +68,46 @@ where
     }
 
     pub fn get_from_addr(&self, addr: usize) -> Option<&(Option<Key>, Value)> {
-        // TODO: test
-        if addr < self.data.len() {
-            return Some(&self.data[addr]);
-        }
-
-        None
+        self.data.get(addr)
     }
 
     /// Attempts to return a value at the given key.
     pub fn get(&self, key: Key) -> Option<&Value> {
-        for (stored_key, value) in self.data.iter() {
-            match stored_key {
-                Some(stored_key) => {
-                    if key == *stored_key {
-                        return Some(value);
+        self.index.get(&key).map(|&addr| &self.data[addr].1)
+    }
+
+    /// Removes every entry from the given address onward. Values they shadowed are returned by lookups again.
+    pub fn truncate(&mut self, addr: Addr) {
+        if addr >= self.data.len() {
+            return;
+        }
+
+        for (key, _) in self.data.split_off(addr) {
+            if let Some(key) = key {
+                match self
+                    .data
+                    .iter()
+                    .rposition(|(k, _)| k.as_ref() == Some(&key))
+                {
+                    Some(shadowed) => {
+                        self.index.insert(key, shadowed);
+                    }
+                    None => {
+                        self.index.remove(&key);
                     }
                 }
-                None => {}
             }
         }
-
-        None
     }
 
     pub fn clear(&mut self) {
         self.data.clear();
+        self.index.clear();
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison, unused_must_use)]
 mod tests {
     use super::*;
 
@@ -141,13 +128,15 @@ mod tests {
     }
 
     #[test]
-    fn insert_removes_old_value() {
+    fn insert_shadows_old_value() {
         let cap = 30201;
         let mut d = Dictionary::<i32, i32>::new(cap);
-        d.insert(Some(4), 3).unwrap();
-        d.insert(Some(4), 5).unwrap();
+        let old = d.insert(Some(4), 3).unwrap();
+        let new = d.insert(Some(4), 5).unwrap();
 
-        assert_eq!((Some(4), 5), d.dictionary()[0]);
+        assert_eq!(Some(&5), d.get(4));
+        assert_eq!(Some(&(Some(4), 3)), d.get_from_addr(old));
+        assert_eq!(Some(&(Some(4), 5)), d.get_from_addr(new));
     }
 
     #[test]
@@ -178,4 +167,58 @@ mod tests {
         d.clear();
         assert_eq!(true, d.data.is_empty());
     }
+
+    #[test]
+    fn clear_wipes_index() {
+        let cap = 30201;
+        let mut d = Dictionary::<i32, i32>::new(cap);
+        d.insert(Some(2), 3).unwrap();
+        d.clear();
+        assert_eq!(None, d.get(2));
+        assert_eq!(None, d.get_addr(2));
+    }
+
+    #[test]
+    fn get_returns_newest_value() {
+        let cap = 30201;
+        let mut d = Dictionary::<i32, i32>::new(cap);
+        d.insert(Some(2), 3).unwrap();
+        d.insert(Some(4), 5).unwrap();
+        d.insert(Some(2), 6).unwrap();
+
+        assert_eq!(Some(&6), d.get(2));
+        assert_eq!(Some(&5), d.get(4));
+        assert_eq!(Some(1), d.get_addr(4));
+        assert_eq!(Some(2), d.get_addr(2));
+    }
+
+    #[test]
+    fn truncate_restores_shadowed_values() {
+        let cap = 30201;
+        let mut d = Dictionary::<i32, i32>::new(cap);
+        d.insert(Some(2), 3).unwrap();
+        let addr = d.insert(Some(4), 5).unwrap();
+        d.insert(Some(2), 6).unwrap();
+        d.insert(Some(7), 8).unwrap();
+
+        d.truncate(addr);
+        assert_eq!(1, d.dictionary().len());
+        assert_eq!(Some(&3), d.get(2));
+        assert_eq!(None, d.get(4));
+        assert_eq!(None, d.get(7));
+
+        d.truncate(5);
+        assert_eq!(1, d.dictionary().len());
+    }
+
+    #[test]
+    fn unnamed_entries_are_not_indexed() {
+        let cap = 30201;
+        let mut d = Dictionary::<i32, i32>::new(cap);
+        d.insert(None, 3).unwrap();
+        d.insert(Some(4), 5).unwrap();
+
+        assert_eq!(Some(1), d.get_addr(4));
+        assert_eq!((None, 3), d.dictionary()[0]);
+    }
 }
@@ -1,32 +1,72 @@
-const ID_SIZE: usize = 16; // Arbitrary
-pub type Identifier = [char; ID_SIZE];
+use std::collections::HashMap;
 
-fn id(s: &str) -> Identifier {
-    let mut id = [char::default(); ID_SIZE];
+/// A cheap handle for a name interned by an `Interner`. Two ids are equal exactly when their names are.
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+pub struct Id(usize);
 
-    for (i, c) in s.chars().take(ID_SIZE).enumerate() {
-        id[i] = c;
+/// A symbol table giving each distinct name, of any length, its own `Id`.
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    names: Vec<String>,
+    ids: HashMap<String, Id>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    id
-}
+    /// Returns the id of the name, interning it first if it has not been seen before.
+    pub fn intern(&mut self, name: &str) -> Id {
+        if let Some(id) = self.get(name) {
+            return id;
+        }
 
-#[derive(PartialEq, Copy, Clone)]
-pub struct Id {
-    id: Identifier,
-}
+        let id = Id(self.names.len());
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+
+        id
+    }
 
-impl std::fmt::Debug for Id {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        use std::iter::FromIterator;
-        let id = String::from_iter(&self.id);
+    /// Returns the id of the name, or `None` if it has never been interned.
+    pub fn get(&self, name: &str) -> Option<Id> {
+        self.ids.get(name).copied()
+    }
 
-        f.write_str(&id)
+    /// Returns the name the id was interned from.
+    pub fn name(&self, id: Id) -> &str {
+        &self.names[id.0]
     }
 }
 
-impl From<&str> for Id {
-    fn from(s: &str) -> Self {
-        Self { id: id(s) }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intern_returns_same_id_for_same_name() {
+        let mut i = Interner::new();
+        let a = i.intern("dup");
+        let b = i.intern("drop");
+        assert_eq!(a, i.intern("dup"));
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn long_names_are_distinct() {
+        let mut i = Interner::new();
+        let a = i.intern("compute-average-latency");
+        let b = i.intern("compute-average-latency-ms");
+        assert_ne!(a, b);
+        assert_eq!("compute-average-latency-ms", i.name(b));
+    }
+
+    #[test]
+    fn get_does_not_intern() {
+        let mut i = Interner::new();
+        assert_eq!(None, i.get("missing"));
+        let id = i.intern("missing");
+        assert_eq!(Some(id), i.get("missing"));
     }
 }
@@ -0,0 +1,9 @@
+//! A Forth-like interpreter. `context::Context` evaluates lines of code against its stacks, dictionary and
+//! data space.
+
+pub mod cell;
+pub mod context;
+pub mod data_space;
+pub mod dictionary;
+pub mod id;
+pub mod stack;
@@ -1,13 +1,14 @@
 use std::io;
 use std::io::Write;
-
-mod context;
-mod dictionary;
-mod id;
-mod stack;
+use valkyrie::context;
 
 fn main() {
-    let mut forth = context::Context::new(i16::MAX as usize, 666);
+    let mut forth = context::Context::<context::Datum>::with_settings(
+        i16::MAX as usize,
+        666,
+        u16::MAX as usize,
+        context::Settings::default(),
+    );
 
     loop {
         print!("go-forth> ");
@@ -16,23 +17,28 @@ fn main() {
         // Do the reading
         let mut input = String::new();
         match io::stdin().read_line(&mut input) {
-            Ok(size) => match forth.eval(input) {
-                Ok(result) => match result {
-                    context::Return::Ok => {
-                        println!("OK -> STACK {:?}", forth.stack());
-                    }
-                    context::Return::Shutdown => {
-                        println!("OK: Shutting down...");
-                        return;
-                    }
-                    context::Return::Yielding => {
-                        println!("Doing a yield.");
+            Ok(_) => {
+                let result = forth.eval(input);
+                print!("{}", forth.take_output());
+
+                match result {
+                    Ok(result) => match result {
+                        context::Return::Ok => {
+                            println!("OK -> STACK {:?}", forth.stack());
+                        }
+                        context::Return::Shutdown => {
+                            println!("OK: Shutting down...");
+                            return;
+                        }
+                        context::Return::Yielding => {
+                            println!("Doing a yield.");
+                        }
+                    },
+                    Err(error) => {
+                        println!("ERROR: {:?}", error);
                     }
-                },
-                Err(error) => {
-                    println!("ERROR: {:?}", error);
                 }
-            },
+            }
             Err(error) => {
                 println!("ERROR: {:?}", error);
             }
@@ -25,6 +25,16 @@ impl<Data> Stack<Data> {
         &self.data
     }
 
+    /// Returns the number of items on the stack.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns whether the stack holds no items.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
     /// Pushes a new item onto the stack.
     pub fn push(&mut self, data: Data) -> Result<(), StackErr> {
         if self.data.len() < self.capacity {
@@ -48,9 +58,30 @@ impl<Data> Stack<Data> {
             None => Err(StackErr::Underflow),
         }
     }
+
+    /// Returns the item at the given depth, where 0 is the top of the stack.
+    pub fn peek(&self, depth: usize) -> Result<&Data, StackErr> {
+        match self.index(depth) {
+            Some(i) => Ok(&self.data[i]),
+            None => Err(StackErr::Underflow),
+        }
+    }
+
+    /// Removes the item at the given depth, where 0 is the top of the stack.
+    pub fn remove(&mut self, depth: usize) -> Result<Data, StackErr> {
+        match self.index(depth) {
+            Some(i) => Ok(self.data.remove(i)),
+            None => Err(StackErr::Underflow),
+        }
+    }
+
+    fn index(&self, depth: usize) -> Option<usize> {
+        self.data.len().checked_sub(depth)?.checked_sub(1)
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison, unused_variables)]
 mod tests {
     use super::*;
 
@@ -126,4 +157,29 @@ mod tests {
         assert_eq!(false, result.is_ok());
         assert_eq!(StackErr::Underflow, result.unwrap_err());
     }
+
+    #[test]
+    fn peek_returns_item_at_depth() {
+        let mut stack = Stack::new(3);
+        stack.push(1).unwrap();
+        stack.push(2).unwrap();
+        stack.push(3).unwrap();
+
+        assert_eq!(Ok(&3), stack.peek(0));
+        assert_eq!(Ok(&1), stack.peek(2));
+        assert_eq!(Err(StackErr::Underflow), stack.peek(3));
+        assert_eq!(3, stack.len());
+    }
+
+    #[test]
+    fn remove_takes_item_at_depth() {
+        let mut stack = Stack::new(3);
+        stack.push(1).unwrap();
+        stack.push(2).unwrap();
+        stack.push(3).unwrap();
+
+        assert_eq!(Ok(2), stack.remove(1));
+        assert_eq!(&[1, 3], stack.data());
+        assert_eq!(Err(StackErr::Underflow), stack.remove(2));
+    }
 }